@@ -1,10 +1,65 @@
 // Console interface for the tic-tac-toe game.
 
+use crate::protocol::ExternalBot;
+use crate::record::{load_game, save_game};
+use rust_tac_toe_engine::agent::{Agent, HeuristicPlayer, HumanPlayer};
+use rust_tac_toe_engine::ai::{AiPlayer, Difficulty};
+use rust_tac_toe_engine::analysis::Hints;
+use rust_tac_toe_engine::mcts::{Budget, Mcts};
+use rust_tac_toe_engine::rules::RuleSet;
 use rust_tac_toe_engine::{GameEngine, GameStatus, InvalidGameMoveError, Player, Position};
 use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn run() {
-    let mut engine = GameEngine::new();
+/// Playouts per move for a plain `mcts` agent.
+const MCTS_PLAYOUTS: u32 = 2_000;
+
+/// The agent playing one side of the console game, a person at the prompt or not.
+pub type Seat = Box<dyn Agent>;
+
+/// Build the agent for one side of the console game: `human` for moves typed at the
+/// prompt, which also offers undo, reset, save and load, or an agent name understood
+/// by [`agent_from_spec`].
+pub fn seat_from_spec(spec: &str, seed: u64) -> Result<Seat, String> {
+    match spec {
+        "human" => Ok(Box::<HumanPlayer>::default()),
+        _ => agent_from_spec(spec, seed),
+    }
+}
+
+/// Build an agent from its name: `heuristic`, `minimax`, `mcts` or `mcts:<playouts>`,
+/// a difficulty name such as `random`, or `bot:<command>` for an external bot.
+pub fn agent_from_spec(spec: &str, seed: u64) -> Result<Box<dyn Agent>, String> {
+    if let Some(command) = spec.strip_prefix("bot:") {
+        return Ok(Box::new(ExternalBot::spawn(command)?));
+    }
+    if let Some(playouts) = spec.strip_prefix("mcts") {
+        let playouts = match playouts.strip_prefix(':') {
+            Some(n) => n
+                .parse()
+                .map_err(|_| format!("invalid playout count '{}'", n))?,
+            None if playouts.is_empty() => MCTS_PLAYOUTS,
+            None => return Err(format!("unknown agent '{}'", spec)),
+        };
+        return Ok(Box::new(Mcts::new(Budget::Iterations(playouts), seed)));
+    }
+    let difficulty = match spec {
+        "heuristic" => return Ok(Box::new(HeuristicPlayer)),
+        "minimax" => Difficulty::Perfect,
+        _ => Difficulty::from_name(spec).ok_or_else(|| format!("unknown agent '{}'", spec))?,
+    };
+    Ok(Box::new(AiPlayer::new(difficulty, seed)))
+}
+
+/// Run the interactive game under `rules` with `x` and `o` seated on either side.
+pub fn run(x: Seat, o: Seat, rules: RuleSet) {
+    let mut engine = GameEngine::with_rules(rules);
+    announce_rules(&engine);
+    let mut seats = [x, o];
+    let seat = |player: Player| match player {
+        Player::X => 0,
+        Player::O => 1,
+    };
 
     loop {
         println!("{}", board_as_string(&engine));
@@ -23,30 +78,94 @@ pub fn run() {
             }
             GameStatus::Ongoing => {
                 println!("Current player: {:?}", engine.current_player);
-                game_loop(&mut engine);
+                let agent = seats[seat(engine.current_player)].as_mut();
+                if agent.takes_input() {
+                    let played = engine.history().len();
+                    game_loop(&mut engine, agent);
+                    // Taking back a move also takes back the computer's replies
+                    if engine.history().len() < played {
+                        while engine.can_undo() && !seats[seat(engine.current_player)].takes_input()
+                        {
+                            engine.undo();
+                        }
+                        continue;
+                    }
+                }
+                // A human who reset or loaded the game has no move to play
+                let agent = seats[seat(engine.current_player)].as_mut();
+                if !play_agent_move(&mut engine, agent) && !agent.takes_input() {
+                    println!("Error: The computer has no move. Game abandoned.");
+                    break;
+                }
             }
         }
     }
 }
 
-/// Prompt the user to start a new game. Returns true if a new game was started.
+/// Let an agent pick and play a move for the current player. Returns false if it
+/// offered no legal move.
+pub fn play_agent_move(engine: &mut GameEngine, agent: &mut dyn Agent) -> bool {
+    let Some(pos) = agent.choose_move(engine) else {
+        return false;
+    };
+    if !agent.takes_input() {
+        println!("Computer plays {}", pos.to_index());
+    }
+    engine.play_move(pos).is_ok()
+}
+
+pub fn seed_from_clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// Prompt the user to start a new game, under the same rules or switching between
+/// standard and misère. Returns true if a new game was started.
 pub fn start_new_game(engine: &mut GameEngine) -> bool {
-    print!("Press 'r' to reset the game or any other key to exit: ");
+    print!(
+        "Press 'r' to reset the game, 's' or 'm' for a standard or misère game, or any other key to exit: "
+    );
     io::stdout().flush().unwrap();
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
-    if input.trim() != "r" {
-        return false;
+    let rules = match input.trim() {
+        "r" => engine.rules(),
+        "s" => RuleSet::Standard,
+        "m" => RuleSet::Misere,
+        _ => return false,
+    };
+    *engine = GameEngine::with_rules(rules);
+    announce_rules(engine);
+    true
+}
+
+/// Remind the players when three in a row loses.
+fn announce_rules(engine: &GameEngine) {
+    if engine.rules() == RuleSet::Misere {
+        println!("Misère rules: whoever completes a line loses.");
     }
-    engine.reset();
-    return true;
+}
+
+/// Ask whether to play again. Returns true if the user pressed 'r'.
+pub fn wants_new_game() -> bool {
+    print!("Press 'r' to reset the game or any other key to exit: ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim() == "r"
 }
 
 pub fn board_as_string(engine: &GameEngine) -> String {
+    cells_as_string(&engine.board)
+}
+
+/// Render a 3x3 board of symbols with dividers, leaving empty cells blank.
+pub fn cells_as_string(board: &[Option<Player>; 9]) -> String {
     let mut board_str: String = String::new();
     for i in (0..9).step_by(3) {
         // Map the Player enum to a string for display
-        let get_char = |idx: usize| match engine.board[idx] {
+        let get_char = |idx: usize| match board[idx] {
             Some(Player::X) => "X",
             Some(Player::O) => "O",
             None => " ",
@@ -65,33 +184,80 @@ pub fn board_as_string(engine: &GameEngine) -> String {
             board_str.push_str("-----------\n");
         }
     }
-    return board_str;
+    board_str
+}
+
+/// One line per kind of notable move, e.g. `Win at: 2` and `Block at: 5 7`.
+pub fn hints_as_string(hints: &Hints) -> String {
+    if hints.is_empty() {
+        return "No winning, blocking or fork moves.\n".to_string();
+    }
+    let mut text = String::new();
+    for (label, moves) in [
+        ("Win at", &hints.wins),
+        ("Block at", &hints.blocks),
+        ("Fork at", &hints.forks),
+    ] {
+        if !moves.is_empty() {
+            let cells: Vec<String> = moves.iter().map(|p| p.to_index().to_string()).collect();
+            text.push_str(&format!("{}: {}\n", label, cells.join(" ")));
+        }
+    }
+    text
 }
 
-pub fn game_loop(engine: &mut GameEngine) {
-    print!("Enter your move (0-8) or 'r' to reset: ");
+/// Read input for `human` until it picks a move or changes the game.
+pub fn game_loop(engine: &mut GameEngine, human: &mut dyn Agent) {
+    print!(
+        "Enter your move (0-8), 'hint', 'u' to undo, 'save <file>', 'load <file>' or 'r' to reset: "
+    );
     io::stdout().flush().unwrap();
 
     loop {
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
         let input = input.trim();
-        if handle_input(engine, input) {
+        if handle_input(engine, human, input) {
             break;
         }
     }
 }
 
-/// Handle user input for making a move or resetting the game.
-/// Returns true if the input was handled successfully.
-pub fn handle_input(engine: &mut GameEngine, input: &str) -> bool {
+/// Handle user input for making a move, asking for a hint, undoing the last move,
+/// saving or loading the game, or resetting it. A legal move is handed to `human` to
+/// play. Returns true if the input was handled successfully.
+pub fn handle_input(engine: &mut GameEngine, human: &mut dyn Agent, input: &str) -> bool {
     if input == "r" {
         engine.reset();
         return true;
     }
 
+    if let Some(path) = input.strip_prefix("save ") {
+        return save_game(engine, path.trim());
+    }
+
+    if let Some(path) = input.strip_prefix("load ") {
+        return load_game(engine, path.trim());
+    }
+
+    // A hint leaves the game as it is, so keep reading input
+    if input == "hint" {
+        print!("{}", hints_as_string(&engine.hints()));
+        return false;
+    }
+
+    if input == "u" {
+        if engine.undo().is_none() {
+            println!("Error: There are no moves to undo.");
+            return false;
+        }
+        return true;
+    }
+
     let Ok(pos) = input.parse::<u8>() else {
-        println!("Invalid input. Please enter a number between 0 and 8 or 'r' to reset.");
+        println!(
+            "Invalid input. Please enter a number between 0 and 8, 'hint', 'u' to undo or 'r' to reset."
+        );
         return false;
     };
 
@@ -108,15 +274,20 @@ pub fn handle_input(engine: &mut GameEngine, input: &str) -> bool {
             InvalidGameMoveError::SpaceOccupied => {
                 println!("Error: That space is already occupied. Try another.");
             }
+            InvalidGameMoveError::OutOfBounds
+            | InvalidGameMoveError::WrongBoard
+            | InvalidGameMoveError::Floating
+            | InvalidGameMoveError::WrongMoveKind
+            | InvalidGameMoveError::NotInCycle
+            | InvalidGameMoveError::Forbidden => {
+                println!("Invalid position. Please enter a number between 0 and 8.");
+            }
         }
         return false;
     }
 
-    if let Err(e) = engine.play_move(pos) {
-        println!("Error playing move: {:?}", e);
-        return false;
-    }
-    return true;
+    human.input(pos);
+    true
 }
 
 #[cfg(test)]
@@ -124,6 +295,41 @@ mod tests {
     use super::*;
     use rust_tac_toe_engine::{GameEngine, Position};
 
+    /// Type `input` for a human at the prompt, playing the move it picks, if any.
+    fn enter(engine: &mut GameEngine, input: &str) -> bool {
+        let mut human = HumanPlayer::default();
+        let handled = handle_input(engine, &mut human, input);
+        play_agent_move(engine, &mut human);
+        handled
+    }
+
+    #[test]
+    fn test_seat_from_spec() {
+        assert!(seat_from_spec("human", 0).unwrap().takes_input());
+        for spec in ["minimax", "heuristic", "random", "Hard", "mcts", "mcts:500"] {
+            assert!(!seat_from_spec(spec, 0).unwrap().takes_input());
+        }
+        assert!(seat_from_spec("grandmaster", 0).is_err());
+        assert!(seat_from_spec("mcts:many", 0).is_err());
+        assert!(seat_from_spec("mctsx", 0).is_err());
+    }
+
+    #[test]
+    fn test_hints_as_string() {
+        let mut engine = GameEngine::new();
+        assert_eq!(
+            hints_as_string(&engine.hints()),
+            "No winning, blocking or fork moves.\n"
+        );
+        for &p in &[0, 3, 1, 4] {
+            engine.play_move(Position::new(p).unwrap()).unwrap();
+        }
+        assert_eq!(hints_as_string(&engine.hints()), "Win at: 2\nBlock at: 5\n");
+        // Asking for a hint is not a move
+        assert!(!enter(&mut engine, "hint"));
+        assert_eq!(engine.history().len(), 4);
+    }
+
     #[test]
     fn test_print_board() {
         let mut engine = GameEngine::new();
@@ -141,9 +347,26 @@ mod tests {
     fn test_handle_input_reset() {
         let mut engine = GameEngine::new();
         engine.play_move(Position::new(0).unwrap()).unwrap();
-        handle_input(&mut engine, "r");
+        enter(&mut engine, "r");
         assert_eq!(engine.board, [None; 9]);
         assert_eq!(engine.current_player, Player::X);
         assert_eq!(engine.status, GameStatus::Ongoing);
     }
+
+    #[test]
+    fn test_handle_input_undo() {
+        let mut engine = GameEngine::new();
+        enter(&mut engine, "4");
+        enter(&mut engine, "0");
+        assert!(enter(&mut engine, "u"));
+        assert_eq!(engine.board[0], None);
+        assert_eq!(engine.board[4], Some(Player::X));
+        assert_eq!(engine.current_player, Player::O);
+    }
+
+    #[test]
+    fn test_handle_input_undo_empty() {
+        let mut engine = GameEngine::new();
+        assert!(!enter(&mut engine, "u"));
+    }
 }
@@ -0,0 +1,139 @@
+// The console loop shared by the variant games: show the board, announce the result
+// or ask for a move, and handle undo and reset the same way in every game.
+
+use crate::cli::wants_new_game;
+use rust_tac_toe_engine::{GameStatus, Player};
+use std::io::{self, Write};
+
+/// A game that can be played from the console with [`run`].
+pub trait ConsoleGame {
+    /// Whether 'u' takes back the last move.
+    const CAN_UNDO: bool = false;
+
+    /// The board as shown before every turn.
+    fn board_as_string(&self) -> String;
+
+    /// What to announce once the game is over, or `None` while it goes on.
+    fn result(&self) -> Option<String>;
+
+    /// Who is to move, and anything else they should know before they do.
+    fn turn(&self) -> String;
+
+    /// How to enter a move. The undo and reset options are added after it.
+    fn move_prompt(&self) -> String;
+
+    /// Parse and play a move, explaining what was wrong with it if it is rejected.
+    /// Returns true if the move was played.
+    fn play(&mut self, input: &str) -> bool;
+
+    fn reset(&mut self);
+
+    /// Take back the last move. Returns false if there was none.
+    fn undo(&mut self) -> bool {
+        false
+    }
+
+    /// Play a turn that needs no move from the user, such as the computer's reply or
+    /// an opening with its own prompts. Returns true if a turn was played.
+    fn auto_turn(&mut self) -> bool {
+        false
+    }
+}
+
+/// Play games until the user declines a new one.
+pub fn run(game: &mut impl ConsoleGame) {
+    loop {
+        println!("{}", game.board_as_string());
+        if let Some(result) = game.result() {
+            println!("{}", result);
+            if !wants_new_game() {
+                break;
+            }
+            game.reset();
+        } else if !game.auto_turn() {
+            println!("{}", game.turn());
+            game_loop(game);
+        }
+    }
+}
+
+/// The usual announcement for a game that ends in a win or a draw.
+pub fn status_result<L>(status: &GameStatus<L>) -> Option<String> {
+    match status {
+        GameStatus::Win { player, .. } => Some(format!("Game over! Winner: {:?}", player)),
+        GameStatus::Draw => Some("Game over! It's a draw!".to_string()),
+        GameStatus::Ongoing => None,
+    }
+}
+
+/// Who is to move, by symbol.
+pub fn current_player(player: Player) -> String {
+    format!("Current player: {:?}", player)
+}
+
+pub fn prompt<G: ConsoleGame>(game: &G) -> String {
+    if G::CAN_UNDO {
+        format!("{}, 'u' to undo or 'r' to reset: ", game.move_prompt())
+    } else {
+        format!("{} or 'r' to reset: ", game.move_prompt())
+    }
+}
+
+fn game_loop(game: &mut impl ConsoleGame) {
+    print!("{}", prompt(game));
+    io::stdout().flush().unwrap();
+
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        if handle_input(game, input) {
+            break;
+        }
+    }
+}
+
+/// Handle user input for making a move, undoing it where the game allows, or
+/// resetting the game. Returns true if the input was handled successfully.
+pub fn handle_input<G: ConsoleGame>(game: &mut G, input: &str) -> bool {
+    if input == "r" {
+        game.reset();
+        return true;
+    }
+
+    if G::CAN_UNDO && input == "u" {
+        if !game.undo() {
+            println!("Error: There are no moves to undo.");
+            return false;
+        }
+        return true;
+    }
+
+    game.play(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_tac_toe_engine::ultimate::UltimateEngine;
+    use rust_tac_toe_engine::wild::WildGame;
+
+    #[test]
+    fn test_prompt_offers_undo_where_supported() {
+        let mut game = WildGame::default();
+        assert!(prompt(&game).ends_with("(e.g. '4x'), 'u' to undo or 'r' to reset: "));
+        assert!(!handle_input(&mut game, "u"));
+        assert!(handle_input(&mut game, "4x"));
+        assert!(handle_input(&mut game, "u"));
+        assert!(game.history().is_empty());
+
+        // Without undo, 'u' is just an invalid move
+        let mut engine = UltimateEngine::new();
+        assert!(prompt(&engine).ends_with("(each 0-8) or 'r' to reset: "));
+        assert!(!handle_input(&mut engine, "u"));
+        assert!(handle_input(&mut engine, "4 4"));
+        assert!(handle_input(&mut engine, "r"));
+        assert_eq!(engine.active_board, None);
+        assert!(engine.boards.iter().all(|board| board.history().is_empty()));
+    }
+}
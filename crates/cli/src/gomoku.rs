@@ -0,0 +1,292 @@
+// Console interface for Gomoku on a 15x15 board, with freestyle, standard or Renju
+// rules and an optional swap2 opening.
+
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::gomoku::{GomokuRules, Seat, Swap2, Swap2Choice, Swap2Phase};
+use rust_tac_toe_engine::mnk::MnkEngine;
+use rust_tac_toe_engine::{InvalidGameMoveError, Player};
+use std::io::{self, Write};
+
+pub fn run(rules: GomokuRules, swap2: bool) {
+    match rules {
+        GomokuRules::Freestyle => println!("Freestyle rules: five or more in a row wins."),
+        GomokuRules::Standard => println!("Standard rules: exactly five in a row wins."),
+        GomokuRules::Renju => println!(
+            "Renju rules: black (X) needs exactly five and may not make an overline, \
+             a double four or a double three. White (O) wins with five or more."
+        ),
+    }
+    console::run(&mut Gomoku {
+        engine: MnkEngine::gomoku().with_rules(rules),
+        swap2,
+    });
+}
+
+/// A game of Gomoku, possibly opened with swap2.
+struct Gomoku {
+    engine: MnkEngine,
+    /// Whether every new game starts with a swap2 opening.
+    swap2: bool,
+}
+
+impl ConsoleGame for Gomoku {
+    fn board_as_string(&self) -> String {
+        board_as_string(&self.engine)
+    }
+
+    fn result(&self) -> Option<String> {
+        console::status_result(&self.engine.status)
+    }
+
+    fn turn(&self) -> String {
+        console::current_player(self.engine.current_player)
+    }
+
+    fn move_prompt(&self) -> String {
+        "Enter a cell (e.g. 'h8')".to_string()
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let Some(index) = parse_cell(&self.engine, input) else {
+            println!("Invalid input. Please enter a column letter and row number (e.g. 'h8').");
+            return false;
+        };
+
+        if let Err(e) = self.engine.play_move(index) {
+            print_error(e);
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.engine.reset();
+    }
+
+    /// Every new game, including after a reset, starts with the opening.
+    fn auto_turn(&mut self) -> bool {
+        if !self.swap2 || self.engine.board().iter().any(Option::is_some) {
+            return false;
+        }
+        self.engine = opening(self.engine.clone());
+        true
+    }
+}
+
+/// Parse the `--rules` and `--swap2` options of the `gomoku` command.
+pub fn parse_options(options: &[String]) -> Result<(GomokuRules, bool), String> {
+    let mut rules = GomokuRules::Freestyle;
+    let mut swap2 = false;
+    let mut options = options.iter();
+    while let Some(flag) = options.next() {
+        match flag.as_str() {
+            "--swap2" => swap2 = true,
+            "--rules" => {
+                let value = options
+                    .next()
+                    .ok_or_else(|| format!("missing value for '{}'", flag))?;
+                rules = GomokuRules::from_name(value)
+                    .ok_or_else(|| format!("unknown rules '{}'", value))?;
+            }
+            _ => return Err(format!("unknown option '{}'", flag)),
+        }
+    }
+    Ok((rules, swap2))
+}
+
+/// Render the board top row first with empty cells as dots, columns lettered from A
+/// and rows numbered from 1 at the bottom.
+pub fn board_as_string(engine: &MnkEngine) -> String {
+    let mut board_str = String::from("  ");
+    for col in 0..engine.width() {
+        board_str.push(' ');
+        board_str.push(column_letter(col));
+    }
+    board_str.push('\n');
+    for (row, cells) in engine.board().chunks(engine.width()).enumerate() {
+        board_str.push_str(&format!("{:>2}", engine.height() - row));
+        for cell in cells {
+            board_str.push_str(match cell {
+                Some(Player::X) => " X",
+                Some(Player::O) => " O",
+                None => " .",
+            });
+        }
+        board_str.push('\n');
+    }
+    board_str
+}
+
+fn column_letter(col: usize) -> char {
+    (b'A' + col as u8) as char
+}
+
+/// Run a swap2 opening from an empty board until both players have a colour.
+fn opening(engine: MnkEngine) -> MnkEngine {
+    let mut game = Swap2::new(engine);
+    while game.phase() != Swap2Phase::Playing {
+        let player = match game.to_act() {
+            Seat::First => "First player",
+            Seat::Second => "Second player",
+        };
+        match game.phase() {
+            Swap2Phase::SecondChooses => print!(
+                "{}: choose 'black', 'white' or 'two' to place two more stones: ",
+                player
+            ),
+            Swap2Phase::FirstChooses => print!("{}: choose 'black' or 'white': ", player),
+            _ => print!(
+                "{}: place a {} stone: ",
+                player,
+                colour(game.engine().current_player)
+            ),
+        }
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        if handle_opening_input(&mut game, input.trim()) && game.phase() != Swap2Phase::Playing {
+            println!("{}", board_as_string(game.engine()));
+        }
+    }
+    println!(
+        "The first player plays {}, the second player {}.",
+        colour(game.player_of(Seat::First)),
+        colour(game.player_of(Seat::Second))
+    );
+    game.into_engine()
+}
+
+fn colour(player: Player) -> &'static str {
+    match player {
+        Player::X => "black (X)",
+        Player::O => "white (O)",
+    }
+}
+
+/// Handle a stone or a colour choice during a swap2 opening. Returns true if the
+/// input was handled successfully.
+pub fn handle_opening_input(game: &mut Swap2, input: &str) -> bool {
+    let result = match input {
+        "black" => game.choose(Swap2Choice::Black),
+        "white" => game.choose(Swap2Choice::White),
+        "two" => game.choose(Swap2Choice::PlaceTwo),
+        _ => match parse_cell(game.engine(), input) {
+            Some(index) => game.play_move(index),
+            None => {
+                println!("Invalid input. Please enter a cell such as 'h8' or a choice.");
+                return false;
+            }
+        },
+    };
+
+    match result {
+        Ok(()) => true,
+        Err(InvalidGameMoveError::WrongMoveKind) => {
+            match game.phase() {
+                Swap2Phase::SecondChooses => {
+                    println!("Error: Choose 'black', 'white' or 'two' before playing on.")
+                }
+                Swap2Phase::FirstChooses => println!("Error: Choose 'black' or 'white'."),
+                _ => println!("Error: Place the opening stones before choosing."),
+            }
+            false
+        }
+        Err(e) => {
+            print_error(e);
+            false
+        }
+    }
+}
+
+fn print_error(e: InvalidGameMoveError) {
+    match e {
+        InvalidGameMoveError::GameAlreadyWon => {
+            println!("Error: The game is already won! Press 'r' to reset.");
+        }
+        InvalidGameMoveError::SpaceOccupied => {
+            println!("Error: That cell is already occupied. Try another.");
+        }
+        InvalidGameMoveError::Forbidden => {
+            println!("Error: Black may not make an overline, a double four or a double three.");
+        }
+        InvalidGameMoveError::OutOfBounds
+        | InvalidGameMoveError::WrongBoard
+        | InvalidGameMoveError::Floating
+        | InvalidGameMoveError::WrongMoveKind
+        | InvalidGameMoveError::NotInCycle => {
+            println!("Invalid cell. Please enter a cell on the board.");
+        }
+    }
+}
+
+/// Parse "h8" or "H8" into column H, row 8 counted from the bottom.
+fn parse_cell(engine: &MnkEngine, input: &str) -> Option<usize> {
+    let mut chars = input.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    if !letter.is_ascii_uppercase() {
+        return None;
+    }
+    let col = (letter as u8 - b'A') as usize;
+    let number: usize = chars.as_str().parse().ok()?;
+    let row = engine.height().checked_sub(number)?;
+    engine.index(row, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    fn gomoku(engine: MnkEngine) -> Gomoku {
+        Gomoku {
+            engine,
+            swap2: false,
+        }
+    }
+
+    #[test]
+    fn test_print_board() {
+        let mut game = gomoku(MnkEngine::new(5, 4, 4).unwrap());
+        for input in ["a1", "C3", "e4"] {
+            assert!(handle_input(&mut game, input));
+        }
+        assert!(!handle_input(&mut game, "f1"));
+        assert!(!handle_input(&mut game, "a0"));
+        assert!(!handle_input(&mut game, "c3"));
+
+        let expected_str = concat!(
+            "   A B C D E\n",
+            " 4 . . . . X\n",
+            " 3 . . O . .\n",
+            " 2 . . . . .\n",
+            " 1 X . . . .\n",
+        );
+        assert_eq!(board_as_string(&game.engine), expected_str);
+
+        let args: Vec<String> = ["--swap2", "--rules", "renju"].map(String::from).to_vec();
+        assert_eq!(parse_options(&args), Ok((GomokuRules::Renju, true)));
+        assert!(parse_options(&["--rules".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_swap2_opening_and_renju() {
+        let engine = MnkEngine::gomoku().with_rules(GomokuRules::Renju);
+        let mut game = Swap2::new(engine);
+        assert!(!handle_opening_input(&mut game, "black"));
+        for input in ["h8", "h9", "j8"] {
+            assert!(handle_opening_input(&mut game, input));
+        }
+        assert!(!handle_opening_input(&mut game, "k8"));
+        assert!(handle_opening_input(&mut game, "black"));
+        assert_eq!(game.player_of(Seat::Second), Player::X);
+
+        // Black's open threes on row 8 and column I would cross at I8
+        let mut game = gomoku(game.into_engine());
+        for input in ["a1", "i9", "a3", "i10", "a5"] {
+            assert!(handle_input(&mut game, input));
+        }
+        assert!(!handle_input(&mut game, "i8"));
+        assert!(handle_input(&mut game, "k8"));
+    }
+}
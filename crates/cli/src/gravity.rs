@@ -0,0 +1,154 @@
+// Console interface for gravity games such as Connect Four, where moves pick a column.
+
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::mnk::MnkEngine;
+use rust_tac_toe_engine::{InvalidGameMoveError, Player};
+
+pub fn run(engine: MnkEngine) {
+    println!(
+        "Pieces drop to the bottom of their column. Get {} in a row to win.",
+        engine.k()
+    );
+    console::run(&mut Gravity(engine));
+}
+
+/// A gravity board, played by column.
+struct Gravity(MnkEngine);
+
+impl ConsoleGame for Gravity {
+    fn board_as_string(&self) -> String {
+        board_as_string(&self.0)
+    }
+
+    fn result(&self) -> Option<String> {
+        console::status_result(&self.0.status)
+    }
+
+    fn turn(&self) -> String {
+        console::current_player(self.0.current_player)
+    }
+
+    fn move_prompt(&self) -> String {
+        format!("Enter a column (0-{})", self.0.width() - 1)
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let engine = &mut self.0;
+        let Ok(col) = input.parse::<usize>() else {
+            println!(
+                "Invalid input. Please enter a column between 0 and {} or 'r' to reset.",
+                engine.width() - 1
+            );
+            return false;
+        };
+
+        if let Err(e) = engine.play_column(col) {
+            match e {
+                InvalidGameMoveError::GameAlreadyWon => {
+                    println!("Error: The game is already won! Press 'r' to reset.");
+                }
+                InvalidGameMoveError::SpaceOccupied => {
+                    println!("Error: That column is full. Try another.");
+                }
+                InvalidGameMoveError::OutOfBounds
+                | InvalidGameMoveError::WrongBoard
+                | InvalidGameMoveError::Floating
+                | InvalidGameMoveError::WrongMoveKind
+                | InvalidGameMoveError::NotInCycle
+                | InvalidGameMoveError::Forbidden => {
+                    println!(
+                        "Invalid column. Please enter a number between 0 and {}.",
+                        engine.width() - 1
+                    );
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+/// Parse the optional `<width> <height> <k>` of the `gravity` command. Without them
+/// the board is Connect Four's.
+pub fn parse_board(args: &[String]) -> Result<MnkEngine, String> {
+    match args {
+        [] => Ok(MnkEngine::connect_four()),
+        [width, height, k] => {
+            let number = |value: &String| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}'", value))
+            };
+            MnkEngine::new(number(width)?, number(height)?, number(k)?)
+                .map(MnkEngine::with_gravity)
+                .ok_or_else(|| format!("no room for {} in a row on that board", k))
+        }
+        _ => Err("expected a width, a height and k".to_string()),
+    }
+}
+
+/// Render the board top row first with empty cells as dots, and the column numbers
+/// underneath.
+pub fn board_as_string(engine: &MnkEngine) -> String {
+    let mut board_str = String::new();
+    for row in engine.board().chunks(engine.width()) {
+        for cell in row {
+            board_str.push_str(match cell {
+                Some(Player::X) => " X",
+                Some(Player::O) => " O",
+                None => " .",
+            });
+        }
+        board_str.push('\n');
+    }
+    for col in 0..engine.width() {
+        board_str.push_str(&format!(" {}", col % 10));
+    }
+    board_str.push('\n');
+    board_str
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    #[test]
+    fn test_print_board() {
+        let mut game = Gravity(parse_board(&[]).unwrap());
+        for input in ["3", "3", "4"] {
+            assert!(handle_input(&mut game, input));
+        }
+        assert!(!handle_input(&mut game, "7"));
+
+        let expected_str = concat!(
+            " . . . . . . .\n",
+            " . . . . . . .\n",
+            " . . . . . . .\n",
+            " . . . . . . .\n",
+            " . . . O . . .\n",
+            " . . . X X . .\n",
+            " 0 1 2 3 4 5 6\n",
+        );
+        assert_eq!(board_as_string(&game.0), expected_str);
+    }
+
+    #[test]
+    fn test_full_column_and_custom_board() {
+        let args: Vec<String> = ["4", "2", "3"].map(String::from).to_vec();
+        let mut game = Gravity(parse_board(&args).unwrap());
+        assert!(game.0.has_gravity());
+        assert!(handle_input(&mut game, "0"));
+        assert!(handle_input(&mut game, "0"));
+        assert!(!handle_input(&mut game, "0"));
+        assert_eq!(game.0.current_player, Player::X);
+
+        assert!(parse_board(&["3".to_string()]).is_err());
+        let args: Vec<String> = ["3", "3", "5"].map(String::from).to_vec();
+        assert!(parse_board(&args).is_err());
+    }
+}
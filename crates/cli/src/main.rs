@@ -1,4 +1,144 @@
 mod cli;
+mod console;
+mod gomoku;
+mod gravity;
+mod protocol;
+mod quantum;
+mod qubic;
+mod record;
+mod tablebase;
+mod tournament;
+mod ultimate;
+mod wild;
+
+use cli::{Seat, seat_from_spec, seed_from_clock};
+use rust_tac_toe_engine::agent::HumanPlayer;
+use rust_tac_toe_engine::ai::Difficulty;
+use rust_tac_toe_engine::rules::RuleSet;
+
+const USAGE: &str =
+    "Usage: rust-tac-toe-cli [--x <seat>] [--o <seat>] [--ai <random|easy|medium|hard|perfect>]
+                         [--rules <standard|misere>]
+       rust-tac-toe-cli ultimate
+       rust-tac-toe-cli wild
+       rust-tac-toe-cli quantum
+       rust-tac-toe-cli qubic [--ai]
+       rust-tac-toe-cli gravity [<width> <height> <k>]
+       rust-tac-toe-cli gomoku [--rules <freestyle|standard|renju>] [--swap2]
+       rust-tac-toe-cli replay <file>
+       rust-tac-toe-cli bot [--ai <random|easy|medium|hard|perfect>]
+       rust-tac-toe-cli arbiter <x bot command> <o bot command>
+       rust-tac-toe-cli tournament [--games <n>] [--seed <n>] <agent> <agent> [<agent> ...]
+       rust-tac-toe-cli tablebase <file>
+Agents: heuristic, minimax, mcts[:<playouts>], random, easy, medium, hard, perfect or bot:<command>
+Seats: human or an agent; --ai <level> is short for --o <level>";
+
 fn main() {
-    cli::run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let seats = match args.as_slice() {
+        [variant] if variant == "ultimate" => {
+            ultimate::run();
+            return;
+        }
+        [variant] if variant == "wild" => {
+            wild::run();
+            return;
+        }
+        [variant] if variant == "quantum" => {
+            quantum::run();
+            return;
+        }
+        [variant] if variant == "qubic" => {
+            qubic::run(false);
+            return;
+        }
+        [variant, flag] if variant == "qubic" && flag == "--ai" => {
+            qubic::run(true);
+            return;
+        }
+        [variant, size @ ..] if variant == "gravity" => {
+            match gravity::parse_board(size) {
+                Ok(engine) => gravity::run(engine),
+                Err(e) => {
+                    eprintln!("{}.\n{}", e, USAGE);
+                    std::process::exit(2);
+                }
+            }
+            return;
+        }
+        [variant, options @ ..] if variant == "gomoku" => {
+            match gomoku::parse_options(options) {
+                Ok((rules, swap2)) => gomoku::run(rules, swap2),
+                Err(e) => {
+                    eprintln!("{}.\n{}", e, USAGE);
+                    std::process::exit(2);
+                }
+            }
+            return;
+        }
+        [command] if command == "bot" => {
+            protocol::run_bot(Difficulty::Perfect);
+            return;
+        }
+        [command, flag, level] if command == "bot" && flag == "--ai" => {
+            protocol::run_bot(parse_difficulty(level));
+            return;
+        }
+        [command, x, o] if command == "arbiter" => {
+            protocol::run_arbiter(x, o);
+            return;
+        }
+        [command, rest @ ..] if command == "tournament" => {
+            if let Err(e) = tournament::run(rest) {
+                eprintln!("{}.\n{}", e, USAGE);
+                std::process::exit(2);
+            }
+            return;
+        }
+        [command, path] if command == "tablebase" => {
+            tablebase::export(path);
+            return;
+        }
+        [command, path] if command == "replay" => {
+            record::run_replay(path);
+            return;
+        }
+        options => parse_options(options),
+    };
+    match seats {
+        Ok((x, o, rules)) => cli::run(x, o, rules),
+        Err(e) => {
+            eprintln!("{}.\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Parse the `--x`, `--o`, `--ai` and `--rules` options of the interactive game.
+fn parse_options(options: &[String]) -> Result<(Seat, Seat, RuleSet), String> {
+    let mut x: Seat = Box::<HumanPlayer>::default();
+    let mut o: Seat = Box::<HumanPlayer>::default();
+    let mut rules = RuleSet::Standard;
+    for pair in options.chunks(2) {
+        let [flag, value] = pair else {
+            return Err(format!("missing value for '{}'", pair[0]));
+        };
+        match flag.as_str() {
+            "--x" => x = seat_from_spec(value, seed_from_clock())?,
+            "--o" | "--ai" => o = seat_from_spec(value, seed_from_clock())?,
+            "--rules" => {
+                rules =
+                    RuleSet::from_name(value).ok_or_else(|| format!("unknown rules '{}'", value))?
+            }
+            _ => return Err(format!("unknown option '{}'", flag)),
+        }
+    }
+    Ok((x, o, rules))
+}
+
+fn parse_difficulty(level: &str) -> Difficulty {
+    Difficulty::from_name(level).unwrap_or_else(|| {
+        eprintln!("Unknown difficulty '{}'.\n{}", level, USAGE);
+        std::process::exit(2);
+    })
 }
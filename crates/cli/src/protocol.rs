@@ -0,0 +1,451 @@
+// Line-oriented protocol for playing against other programs, in the spirit of UCI.
+//
+// The controller (usually the arbiter) writes one command per line to the bot's stdin
+// and reads replies from its stdout. Squares are board indices 0-8.
+//
+//   protocol                      -> "id name <name>", then "protocolok"
+//   isready                       -> "readyok"
+//   newgame                       start a new game (no reply)
+//   position [startpos] [moves 0 4 8 ...]
+//   position notation <rows> <side> [<rules>] [moves ...]
+//                                 set up the board; notation as in `GameEngine::to_notation`,
+//                                 which names the rules unless they are standard
+//   go                            -> "bestmove <square>", or "bestmove none" if the game is over
+//   quit                          exit
+//
+// A command that cannot be understood gets a single "error <message>" reply.
+
+use crate::cli::{board_as_string, seed_from_clock};
+use rust_tac_toe_engine::agent::Agent;
+use rust_tac_toe_engine::ai::{AiPlayer, Difficulty};
+use rust_tac_toe_engine::record::GameRecord;
+use rust_tac_toe_engine::{GameEngine, GameStatus, Player, Position};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command as Process, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BOT_NAME: &str = "rust-tac-toe";
+/// How long an external bot may take to answer before it is given up on.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long an external bot gets to exit after `quit` before it is killed.
+const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub enum Command {
+    Protocol,
+    IsReady,
+    NewGame,
+    Position(GameEngine),
+    Go,
+    Quit,
+}
+
+/// Parse one line of input. Blank lines parse to `Ok(None)`.
+pub fn parse_command(line: &str) -> Result<Option<Command>, String> {
+    let mut tokens = line.split_whitespace();
+    let Some(name) = tokens.next() else {
+        return Ok(None);
+    };
+    let command = match name {
+        "protocol" => Command::Protocol,
+        "isready" => Command::IsReady,
+        "newgame" => Command::NewGame,
+        "go" => Command::Go,
+        "quit" => Command::Quit,
+        "position" => Command::Position(parse_position(tokens.collect())?),
+        _ => return Err(format!("unknown command '{}'", name)),
+    };
+    Ok(Some(command))
+}
+
+fn parse_position(tokens: Vec<&str>) -> Result<GameEngine, String> {
+    let (mut engine, rest) = match tokens.as_slice() {
+        ["notation", rows, side, rest @ ..] => {
+            // The notation ends with its rules, if any, before the moves
+            let (notation, rest) = match rest {
+                [rules, rest @ ..] if *rules != "moves" => {
+                    (format!("{} {} {}", rows, side, rules), rest)
+                }
+                _ => (format!("{} {}", rows, side), rest),
+            };
+            let engine = GameEngine::from_notation(&notation)
+                .map_err(|e| format!("invalid notation '{}': {:?}", notation, e))?;
+            (engine, rest)
+        }
+        ["startpos", rest @ ..] => (GameEngine::new(), rest),
+        rest => (GameEngine::new(), rest),
+    };
+
+    let moves = match rest {
+        [] => &[][..],
+        ["moves", moves @ ..] => moves,
+        [token, ..] => return Err(format!("unexpected '{}' in position", token)),
+    };
+    for &token in moves {
+        let pos = token
+            .parse::<u8>()
+            .ok()
+            .and_then(Position::new)
+            .ok_or_else(|| format!("invalid square '{}'", token))?;
+        engine
+            .play_move(pos)
+            .map_err(|e| format!("illegal move {}: {:?}", token, e))?;
+    }
+    Ok(engine)
+}
+
+/// The engine's side of the protocol.
+pub struct Bot {
+    engine: GameEngine,
+    ai: AiPlayer,
+}
+
+impl Bot {
+    pub fn new(ai: AiPlayer) -> Self {
+        Self {
+            engine: GameEngine::new(),
+            ai,
+        }
+    }
+
+    /// Apply a command and return the reply lines.
+    pub fn handle(&mut self, command: Command) -> Vec<String> {
+        match command {
+            Command::Protocol => vec![format!("id name {}", BOT_NAME), "protocolok".to_string()],
+            Command::IsReady => vec!["readyok".to_string()],
+            Command::NewGame => {
+                self.engine.reset();
+                Vec::new()
+            }
+            Command::Position(engine) => {
+                self.engine = engine;
+                Vec::new()
+            }
+            Command::Go => {
+                let best = match self.ai.choose_move(&self.engine) {
+                    Some(pos) => pos.to_index().to_string(),
+                    None => "none".to_string(),
+                };
+                vec![format!("bestmove {}", best)]
+            }
+            Command::Quit => Vec::new(),
+        }
+    }
+}
+
+/// Speak the protocol on stdin/stdout until `quit` or end of input.
+pub fn run_bot(difficulty: Difficulty) {
+    let mut bot = Bot::new(AiPlayer::new(difficulty, seed_from_clock()));
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let replies = match parse_command(&line) {
+            Ok(Some(Command::Quit)) => break,
+            Ok(Some(command)) => bot.handle(command),
+            Ok(None) => continue,
+            Err(message) => vec![format!("error {}", message)],
+        };
+        for reply in replies {
+            writeln!(stdout, "{}", reply).unwrap();
+        }
+        stdout.flush().unwrap();
+    }
+}
+
+/// A bot running in a child process.
+pub struct ExternalBot {
+    pub name: String,
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines from the bot's stdout, read on their own thread so a bot that stops
+    /// answering can be timed out.
+    lines: Receiver<io::Result<String>>,
+    timeout: Duration,
+}
+
+impl ExternalBot {
+    /// Start `command` (a program followed by its arguments) and perform the handshake.
+    pub fn spawn(command: &str) -> Result<Self, String> {
+        Self::spawn_with_timeout(command, REPLY_TIMEOUT)
+    }
+
+    /// Like [`ExternalBot::spawn`], giving up on any reply that takes longer than
+    /// `timeout`.
+    pub fn spawn_with_timeout(command: &str, timeout: Duration) -> Result<Self, String> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or("empty bot command")?;
+        let mut child = Process::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("could not start '{}': {}", command, e))?;
+
+        let stdin = child.stdin.take().ok_or("bot has no stdin")?;
+        let stdout = BufReader::new(child.stdout.take().ok_or("bot has no stdout")?);
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut bot = Self {
+            name: command.to_string(),
+            child,
+            stdin,
+            lines,
+            timeout,
+        };
+
+        bot.send("protocol")?;
+        loop {
+            let line = bot.read_line()?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                bot.name = name.trim().to_string();
+            } else if line.trim() == "protocolok" {
+                break;
+            }
+        }
+        bot.send("newgame")?;
+        Ok(bot)
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|()| self.stdin.flush())
+            .map_err(|e| format!("{} stopped listening: {}", self.name, e))
+    }
+
+    fn read_line(&mut self) -> Result<String, String> {
+        match self.lines.recv_timeout(self.timeout) {
+            Ok(Ok(line)) => Ok(line),
+            Ok(Err(e)) => Err(format!("{} could not be read: {}", self.name, e)),
+            Err(RecvTimeoutError::Timeout) => Err(format!(
+                "{} did not reply within {:?}",
+                self.name, self.timeout
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err(format!("{} exited", self.name)),
+        }
+    }
+
+    /// Ask the bot for its move in the game played so far.
+    pub fn request_move(&mut self, engine: &GameEngine) -> Result<Position, String> {
+        self.send(&position_command(engine))?;
+        self.send("go")?;
+
+        loop {
+            let line = self.read_line()?;
+            let Some(reply) = bestmove_reply(&line) else {
+                continue;
+            };
+            return reply
+                .parse::<u8>()
+                .ok()
+                .and_then(Position::new)
+                .ok_or_else(|| format!("{} sent an invalid move '{}'", self.name, reply));
+        }
+    }
+}
+
+/// The square in a `bestmove <square>` reply, or `None` if `line` is some other reply.
+fn bestmove_reply(line: &str) -> Option<&str> {
+    let mut tokens = line.split_whitespace();
+    (tokens.next() == Some("bestmove")).then(|| tokens.next().unwrap_or_default())
+}
+
+/// The `position` command that sets up `engine` on a bot: the position the move
+/// history starts from, as `startpos` for an empty standard board and in notation
+/// otherwise, then the moves.
+pub fn position_command(engine: &GameEngine) -> String {
+    let mut start = *engine;
+    while start.undo().is_some() {}
+    let mut command = if start.board == [None; 9] && start.rules().is_standard() {
+        "position startpos".to_string()
+    } else {
+        format!("position notation {}", start.to_notation())
+    };
+
+    let moves: Vec<String> = engine
+        .history()
+        .iter()
+        .map(|pos| pos.to_index().to_string())
+        .collect();
+    if !moves.is_empty() {
+        command.push_str(" moves ");
+        command.push_str(&moves.join(" "));
+    }
+    command
+}
+
+impl Agent for ExternalBot {
+    fn choose_move(&mut self, engine: &GameEngine) -> Option<Position> {
+        self.request_move(engine)
+            .map_err(|e| eprintln!("Error: {}", e))
+            .ok()
+    }
+}
+
+impl Drop for ExternalBot {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        // A bot that is stuck thinking would never read the quit, so stop it
+        let deadline = Instant::now() + QUIT_TIMEOUT;
+        while matches!(self.child.try_wait(), Ok(None)) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Play one game between two external bots, printing the board after each move and
+/// the game record at the end. A bot that crashes, stops answering or plays an
+/// illegal move forfeits. Exits with status 1 if a bot cannot be started.
+pub fn run_arbiter(x_command: &str, o_command: &str) {
+    let spawn = |command| {
+        ExternalBot::spawn(command).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    };
+    let mut x = spawn(x_command);
+    let mut o = spawn(o_command);
+    println!("{} (X) vs {} (O)", x.name, o.name);
+
+    let mut engine = GameEngine::new();
+    let mut forfeited = None;
+    while engine.status == GameStatus::Ongoing {
+        let player = engine.current_player;
+        let bot = match player {
+            Player::X => &mut x,
+            Player::O => &mut o,
+        };
+        let played = bot.request_move(&engine).and_then(|pos| {
+            engine
+                .play_move(pos)
+                .map(|()| pos)
+                .map_err(|e| format!("{} played {} illegally: {:?}", bot.name, pos.to_index(), e))
+        });
+        match played {
+            Ok(pos) => {
+                println!("{:?} plays {}", player, pos.to_index());
+                println!("{}", board_as_string(&engine));
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                println!("{:?} forfeits. Winner: {:?}", player, player.next());
+                forfeited = Some(player);
+                break;
+            }
+        }
+    }
+
+    match engine.status {
+        GameStatus::Win { player, .. } => println!("Game over! Winner: {:?}", player),
+        GameStatus::Draw => println!("Game over! It's a draw!"),
+        GameStatus::Ongoing => {}
+    }
+    let date = crate::record::today();
+    let mut record = GameRecord::from_engine(&engine, &x.name, &o.name, &date);
+    if let Some(loser) = forfeited {
+        record = record.forfeited_by(loser);
+    }
+    print!("{}", record.to_text());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_tac_toe_engine::rules::RuleSet;
+
+    fn position(line: &str) -> GameEngine {
+        match parse_command(line) {
+            Ok(Some(Command::Position(engine))) => engine,
+            _ => panic!("{} did not parse as a position", line),
+        }
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let engine = position("position moves 0 4 8");
+        assert_eq!(engine.to_notation(), "X2/1O1/2X O");
+        assert_eq!(engine.history().len(), 3);
+        assert_eq!(position("position startpos").board, [None; 9]);
+        assert_eq!(
+            position("position notation X2/1O1/3 X moves 2").to_notation(),
+            "X1X/1O1/3 O"
+        );
+    }
+
+    #[test]
+    fn test_position_command() {
+        let engine = position("position moves 0 4");
+        assert_eq!(position_command(&engine), "position startpos moves 0 4");
+        assert_eq!(position_command(&GameEngine::new()), "position startpos");
+
+        let mut misere = GameEngine::with_rules(RuleSet::Misere);
+        misere.play_move(Position::new(4).unwrap()).unwrap();
+        let command = position_command(&misere);
+        assert_eq!(command, "position notation 3/3/3 X misere moves 4");
+        let parsed = position(&command);
+        assert_eq!(parsed.rules(), RuleSet::Misere);
+        assert_eq!(parsed.board, misere.board);
+
+        // Without a move history the position itself is sent
+        let set_up = GameEngine::from_notation("X2/1O1/3 X").unwrap();
+        assert_eq!(position_command(&set_up), "position notation X2/1O1/3 X");
+        assert_eq!(position(&position_command(&set_up)).board, set_up.board);
+    }
+
+    #[test]
+    fn test_bestmove_reply() {
+        assert_eq!(bestmove_reply("bestmove 4\n"), Some("4"));
+        assert_eq!(bestmove_reply("  bestmove   none"), Some("none"));
+        assert_eq!(bestmove_reply("bestmove"), Some(""));
+        assert_eq!(bestmove_reply("bestmovex 3"), None);
+        assert_eq!(bestmove_reply("info bestmove 3"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_silent_bot_times_out() {
+        // `cat` echoes the handshake back but never says protocolok
+        let started = Instant::now();
+        let error = ExternalBot::spawn_with_timeout("cat", Duration::from_millis(100))
+            .err()
+            .unwrap();
+        assert!(error.contains("did not reply"), "{}", error);
+        assert!(started.elapsed() < REPLY_TIMEOUT);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse_command("   "), Ok(None)));
+        assert!(parse_command("think").is_err());
+        assert!(parse_command("position moves 0 0").is_err());
+        assert!(parse_command("position moves 9").is_err());
+        assert!(parse_command("position notation XXX O").is_err());
+    }
+
+    #[test]
+    fn test_bot_replies() {
+        let mut bot = Bot::new(AiPlayer::new(Difficulty::Perfect, 0));
+        assert_eq!(bot.handle(Command::Protocol).last().unwrap(), "protocolok");
+        assert_eq!(bot.handle(Command::IsReady), vec!["readyok"]);
+
+        // X threatens the top row; O must block at 2
+        bot.handle(Command::Position(position("position moves 0 4 1")));
+        assert_eq!(bot.handle(Command::Go), vec!["bestmove 2"]);
+
+        bot.handle(Command::Position(position("position moves 0 3 1 4 2")));
+        assert_eq!(bot.handle(Command::Go), vec!["bestmove none"]);
+    }
+}
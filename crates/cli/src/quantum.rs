@@ -0,0 +1,195 @@
+// Console interface for quantum tic-tac-toe, where marks are entangled between squares
+// until a cycle collapses them.
+
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::quantum::{Mark, QuantumEngine, QuantumMove, QuantumStatus};
+use rust_tac_toe_engine::{InvalidGameMoveError, Player, Position};
+
+pub fn run() {
+    println!(
+        "Quantum rules: each mark goes in two squares until a cycle of entanglements \
+         collapses it. Lowercase marks are spooky, uppercase ones classical."
+    );
+    console::run(&mut QuantumEngine::new());
+}
+
+impl ConsoleGame for QuantumEngine {
+    fn board_as_string(&self) -> String {
+        board_as_string(self)
+    }
+
+    fn result(&self) -> Option<String> {
+        match self.status {
+            QuantumStatus::Win {
+                player,
+                runner_up: Some(_),
+                ..
+            } => Some(format!(
+                "Game over! {:?} scores 1 point and {:?} scores half a point.",
+                player,
+                player.next()
+            )),
+            QuantumStatus::Win { player, .. } => Some(format!("Game over! Winner: {:?}", player)),
+            QuantumStatus::Draw => Some("Game over! It's a draw!".to_string()),
+            QuantumStatus::Ongoing => None,
+        }
+    }
+
+    fn turn(&self) -> String {
+        console::current_player(self.current_player)
+    }
+
+    fn move_prompt(&self) -> String {
+        match (self.cycle, &self.open_squares()[..]) {
+            (Some(cycle), _) => format!(
+                "{} closed a cycle. Choose the square it collapses into ({} or {})",
+                mark_as_string(cycle.mark, false),
+                cycle.squares[0].to_index(),
+                cycle.squares[1].to_index()
+            ),
+            (None, [square]) => format!(
+                "Only square {} is left. Enter it to fill it",
+                square.to_index()
+            ),
+            _ => "Enter two squares for your mark (e.g. '0 4')".to_string(),
+        }
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let Some(mv) = parse_move(self, input) else {
+            println!(
+                "Invalid input. Please enter one or two squares between 0 and 8 or 'r' to reset."
+            );
+            return false;
+        };
+
+        if let Err(e) = self.play_move(mv) {
+            match e {
+                InvalidGameMoveError::GameAlreadyWon => {
+                    println!("Error: The game is already won! Press 'r' to reset.");
+                }
+                InvalidGameMoveError::SpaceOccupied => {
+                    println!("Error: That square already holds a classical mark. Try another.");
+                }
+                InvalidGameMoveError::NotInCycle => {
+                    println!("Error: The mark can only collapse into one of its two squares.");
+                }
+                InvalidGameMoveError::WrongMoveKind => {
+                    if self.cycle.is_some() {
+                        println!("Error: Choose where the cycle collapses first.");
+                    } else {
+                        println!("Error: A mark needs two different squares.");
+                    }
+                }
+                InvalidGameMoveError::OutOfBounds
+                | InvalidGameMoveError::WrongBoard
+                | InvalidGameMoveError::Floating
+                | InvalidGameMoveError::Forbidden => {
+                    println!("Invalid move. Please enter squares between 0 and 8.");
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        QuantumEngine::reset(self);
+    }
+}
+
+fn mark_as_string(mark: Mark, classical: bool) -> String {
+    let symbol = match (mark.player, classical) {
+        (Player::X, true) => 'X',
+        (Player::O, true) => 'O',
+        (Player::X, false) => 'x',
+        (Player::O, false) => 'o',
+    };
+    format!("{}{}", symbol, mark.turn)
+}
+
+/// Render the board with a classical mark or the spooky marks in each square, all
+/// squares padded to the widest one.
+pub fn board_as_string(engine: &QuantumEngine) -> String {
+    let cells: Vec<String> = (0..9)
+        .filter_map(Position::new)
+        .map(|square| match engine.classical[square.to_index()] {
+            Some(mark) => mark_as_string(mark, true),
+            None => engine
+                .marks_in(square)
+                .map(|mark| mark_as_string(mark, false))
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+        .collect();
+    let width = cells.iter().map(String::len).max().unwrap_or(0).max(2);
+
+    let mut board_str = String::new();
+    for (row, cells) in cells.chunks(3).enumerate() {
+        if row > 0 {
+            board_str.push_str(&"-".repeat(3 * (width + 2) + 2));
+            board_str.push('\n');
+        }
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|cell| format!(" {:width$} ", cell))
+            .collect();
+        board_str.push_str(&cells.join("|"));
+        board_str.push('\n');
+    }
+    board_str
+}
+
+/// Parse "0 4" or "04" into a spooky mark. A single square collapses the pending
+/// cycle into it, or otherwise fills the last open square.
+fn parse_move(engine: &QuantumEngine, input: &str) -> Option<QuantumMove> {
+    let squares: Vec<Position> = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| Position::new(c.to_digit(10)? as u8))
+        .collect::<Option<_>>()?;
+    match squares[..] {
+        [a, b] => Some(QuantumMove::Spooky(a, b)),
+        [square] if engine.cycle.is_some() => Some(QuantumMove::Collapse(square)),
+        [square] => Some(QuantumMove::Classical(square)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    #[test]
+    fn test_print_board() {
+        let mut engine = QuantumEngine::new();
+        for input in ["0 1", "12", "4 8"] {
+            assert!(handle_input(&mut engine, input));
+        }
+        assert!(!handle_input(&mut engine, "9 1"));
+
+        let expected_str = concat!(
+            " x1    | x1 o2 | o2    \n",
+            "-----------------------\n",
+            "       | x3    |       \n",
+            "-----------------------\n",
+            "       |       | x3    \n",
+        );
+        assert_eq!(board_as_string(&engine), expected_str);
+    }
+
+    #[test]
+    fn test_collapse_input() {
+        let mut engine = QuantumEngine::new();
+        for input in ["0 1", "1 2", "2 0"] {
+            assert!(handle_input(&mut engine, input));
+        }
+        // O must collapse X's third mark before placing one
+        assert!(!handle_input(&mut engine, "4 5"));
+        assert!(!handle_input(&mut engine, "1"));
+        assert!(handle_input(&mut engine, "2"));
+        assert_eq!(engine.current_player, Player::O);
+        assert!(board_as_string(&engine).starts_with(" X1 | O2 | X3 \n"));
+    }
+}
@@ -0,0 +1,179 @@
+// Console interface for Qubic, four in a row on a 4x4x4 cube.
+
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::qubic::{self, CubePosition, QubicGame};
+use rust_tac_toe_engine::{InvalidGameMoveError, Player};
+
+/// Play Qubic, with the computer playing O if `computer` is set.
+pub fn run(computer: bool) {
+    println!("Qubic: get four in a row in any direction through the cube.");
+    console::run(&mut Qubic {
+        game: QubicGame::default(),
+        computer,
+    });
+}
+
+/// A game of Qubic, possibly against the computer.
+struct Qubic {
+    game: QubicGame,
+    /// Whether the computer plays O.
+    computer: bool,
+}
+
+impl ConsoleGame for Qubic {
+    const CAN_UNDO: bool = true;
+
+    fn board_as_string(&self) -> String {
+        board_as_string(&self.game)
+    }
+
+    fn result(&self) -> Option<String> {
+        console::status_result(self.game.status())
+    }
+
+    fn turn(&self) -> String {
+        console::current_player(self.game.current_player())
+    }
+
+    fn move_prompt(&self) -> String {
+        "Enter your move as layer, row and column (e.g. '1 2 3')".to_string()
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let Some(pos) = parse_position(input) else {
+            println!("Invalid input. Please enter a layer, row and column, each between 0 and 3.");
+            return false;
+        };
+
+        if let Err(e) = self.game.play_move(pos) {
+            match e {
+                InvalidGameMoveError::GameAlreadyWon => {
+                    println!("Error: The game is already won! Press 'r' to reset.");
+                }
+                InvalidGameMoveError::SpaceOccupied => {
+                    println!("Error: That space is already occupied. Try another.");
+                }
+                InvalidGameMoveError::OutOfBounds
+                | InvalidGameMoveError::WrongBoard
+                | InvalidGameMoveError::Floating
+                | InvalidGameMoveError::WrongMoveKind
+                | InvalidGameMoveError::NotInCycle
+                | InvalidGameMoveError::Forbidden => {
+                    println!("Invalid position. Please enter numbers between 0 and 3.");
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.game.reset();
+    }
+
+    /// Against the computer, undo also takes back its reply.
+    fn undo(&mut self) -> bool {
+        if self.game.undo().is_none() {
+            return false;
+        }
+        if self.computer && self.game.current_player() == Player::O {
+            self.game.undo();
+        }
+        true
+    }
+
+    fn auto_turn(&mut self) -> bool {
+        if !self.computer || self.game.current_player() != Player::O {
+            return false;
+        }
+        if let Some(pos) = qubic::best_move(&self.game) {
+            println!("Computer plays {} {} {}", pos.layer(), pos.row(), pos.col());
+            let _ = self.game.play_move(pos);
+        }
+        true
+    }
+}
+
+/// Render the four layers side by side, lowest first, with empty cells as dots.
+pub fn board_as_string(game: &QubicGame) -> String {
+    let mut board_str = (0..4)
+        .map(|layer| format!("Layer {}", layer))
+        .collect::<Vec<_>>()
+        .join("   ");
+    board_str.push('\n');
+    for row in 0..4 {
+        let layers: Vec<String> = (0..4)
+            .map(|layer| {
+                (0..4)
+                    .filter_map(|col| CubePosition::new(layer, row, col))
+                    .map(|pos| match game.board().get(pos) {
+                        Some(Player::X) => "X",
+                        Some(Player::O) => "O",
+                        None => ".",
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        board_str.push_str(&layers.join("   "));
+        board_str.push('\n');
+    }
+    board_str
+}
+
+/// Parse "1 2 3" or "123" into layer 1, row 2, column 3.
+fn parse_position(input: &str) -> Option<CubePosition> {
+    let digits: Vec<u8> = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(10).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    match digits[..] {
+        [layer, row, col] => CubePosition::new(layer, row, col),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    fn qubic(computer: bool) -> Qubic {
+        Qubic {
+            game: QubicGame::default(),
+            computer,
+        }
+    }
+
+    #[test]
+    fn test_print_board() {
+        let mut game = qubic(false);
+        for input in ["0 0 0", "123", "3 3 3"] {
+            assert!(handle_input(&mut game, input));
+        }
+        assert!(!handle_input(&mut game, "4 0 0"));
+        assert!(!handle_input(&mut game, "1 2 3"));
+
+        let expected_str = concat!(
+            "Layer 0   Layer 1   Layer 2   Layer 3\n",
+            "X . . .   . . . .   . . . .   . . . .\n",
+            ". . . .   . . . .   . . . .   . . . .\n",
+            ". . . .   . . . O   . . . .   . . . .\n",
+            ". . . .   . . . .   . . . .   . . . X\n",
+        );
+        assert_eq!(board_as_string(&game.game), expected_str);
+    }
+
+    #[test]
+    fn test_undo_against_computer() {
+        let mut game = qubic(true);
+        assert!(!game.auto_turn());
+        assert!(handle_input(&mut game, "000"));
+        assert!(game.auto_turn());
+        assert_eq!(game.game.history().len(), 2);
+        assert!(handle_input(&mut game, "u"));
+        assert!(game.game.history().is_empty());
+        assert!(!handle_input(&mut game, "u"));
+    }
+}
@@ -0,0 +1,147 @@
+// Saving, loading and replaying game records from the console.
+
+use crate::cli::board_as_string;
+use rust_tac_toe_engine::GameEngine;
+use rust_tac_toe_engine::record::GameRecord;
+use std::fs;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Write the moves played so far in `engine` to `path`. Returns true on success.
+pub fn save_game(engine: &GameEngine, path: &str) -> bool {
+    let record = GameRecord::from_engine(engine, "?", "?", &today());
+    match fs::write(path, record.to_text()) {
+        Ok(()) => {
+            println!("Game saved to {}", path);
+            true
+        }
+        Err(e) => {
+            println!("Error: Could not save to {}: {}", path, e);
+            false
+        }
+    }
+}
+
+/// Replace `engine` with the final position of the game recorded in `path`.
+/// The loaded moves can be undone. Returns true on success.
+pub fn load_game(engine: &mut GameEngine, path: &str) -> bool {
+    match read_record(path) {
+        Some(record) => {
+            *engine = record.replay().last().unwrap_or_default();
+            println!("Loaded {} moves from {}", record.moves.len(), path);
+            true
+        }
+        None => false,
+    }
+}
+
+fn read_record(path: &str) -> Option<GameRecord> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Error: Could not read {}: {}", path, e);
+            return None;
+        }
+    };
+    match GameRecord::parse(&text) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            println!("Error: {} is not a valid game record: {:?}", path, e);
+            None
+        }
+    }
+}
+
+/// Step through a recorded game one move at a time.
+pub fn run_replay(path: &str) {
+    let Some(record) = read_record(path) else {
+        return;
+    };
+    println!(
+        "{} (X) vs {} (O), {}, result {}",
+        record.x_player,
+        record.o_player,
+        record.date,
+        record.result.as_str()
+    );
+
+    for (ply, engine) in record.replay().enumerate() {
+        if ply > 0 {
+            let pos = record.moves[ply - 1];
+            println!(
+                "Move {}: {:?} plays {}",
+                ply,
+                engine.current_player.next(),
+                pos.to_index()
+            );
+        }
+        println!("{}", board_as_string(&engine));
+        if ply == record.moves.len() {
+            break;
+        }
+
+        print!("Press Enter for the next move or 'q' to quit: ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 || input.trim() == "q" {
+            return;
+        }
+    }
+    if record.forfeit {
+        println!("End of game: {} by forfeit", record.result.as_str());
+    } else {
+        println!("End of game: {}", record.result.as_str());
+    }
+}
+
+/// Today's date in record format (`YYYY.MM.DD`, UTC).
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_tac_toe_engine::Position;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("rust-tac-toe-cli-test.ttt");
+        let path = path.to_str().unwrap();
+
+        let mut engine = GameEngine::new();
+        for &p in [4, 0, 8].iter() {
+            engine.play_move(Position::new(p).unwrap()).unwrap();
+        }
+        assert!(save_game(&engine, path));
+
+        let mut loaded = GameEngine::new();
+        assert!(load_game(&mut loaded, path));
+        assert_eq!(loaded.board, engine.board);
+        assert_eq!(loaded.current_player, engine.current_player);
+        assert_eq!(loaded.undo(), Position::new(8));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_today_format() {
+        let date = today();
+        assert_eq!(date.len(), 10);
+        assert_eq!(&date[4..5], ".");
+        assert!(date[..4].parse::<u32>().unwrap() >= 2024);
+    }
+}
@@ -0,0 +1,16 @@
+// Exporting the solved game for other tools.
+
+use rust_tac_toe_engine::tablebase::Tablebase;
+use std::fs;
+
+/// Solve the game and write the tablebase to `path`.
+pub fn export(path: &str) {
+    let table = Tablebase::solve();
+    match fs::write(path, table.to_bytes()) {
+        Ok(()) => println!("Wrote {} positions to {}", table.len(), path),
+        Err(e) => {
+            eprintln!("Error: Could not write {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// Round-robin tournaments between computer players, used to regression-test the AI.
+
+use crate::cli::agent_from_spec;
+use rust_tac_toe_engine::agent::{self, Agent};
+use rust_tac_toe_engine::rng::Rng;
+use rust_tac_toe_engine::{GameStatus, Player};
+
+/// The result of one tournament game. `x` and `o` are entrant indices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameOutcome {
+    pub x: usize,
+    pub o: usize,
+    /// `None` for a draw.
+    pub winner: Option<Player>,
+    /// Number of moves played.
+    pub length: usize,
+}
+
+/// Play one game. An agent that fails to produce a legal move loses.
+pub fn play_game(x: &mut dyn Agent, o: &mut dyn Agent) -> (Option<Player>, usize) {
+    let engine = agent::play_game(x, o);
+    let winner = match engine.status {
+        GameStatus::Win { player, .. } => Some(player),
+        GameStatus::Draw => None,
+        // Stopped early: the side to move forfeits
+        GameStatus::Ongoing => Some(engine.current_player.next()),
+    };
+    (winner, engine.history().len())
+}
+
+/// Play every pair of entrants against each other `games` times, alternating who
+/// moves first.
+pub fn round_robin(agents: &mut [Box<dyn Agent>], games: usize) -> Vec<GameOutcome> {
+    let mut outcomes = Vec::new();
+    for a in 0..agents.len() {
+        for b in a + 1..agents.len() {
+            for game in 0..games {
+                let (x, o) = if game % 2 == 0 { (a, b) } else { (b, a) };
+                let (left, right) = agents.split_at_mut(b);
+                let (first, second) = (left[a].as_mut(), right[0].as_mut());
+                let (winner, length) = if x == a {
+                    play_game(first, second)
+                } else {
+                    play_game(second, first)
+                };
+                outcomes.push(GameOutcome {
+                    x,
+                    o,
+                    winner,
+                    length,
+                });
+            }
+        }
+    }
+    outcomes
+}
+
+/// Wins, draws and losses for one entrant.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Score {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Score {
+    fn add(&mut self, outcome: &GameOutcome, player: Player) {
+        match outcome.winner {
+            None => self.draws += 1,
+            Some(winner) if winner == player => self.wins += 1,
+            Some(_) => self.losses += 1,
+        }
+    }
+
+    /// A win is worth one point and a draw half a point.
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+}
+
+/// `table[a][b]` is `a`'s score in games against `b`.
+pub fn cross_table(entrants: usize, outcomes: &[GameOutcome]) -> Vec<Vec<Score>> {
+    let mut table = vec![vec![Score::default(); entrants]; entrants];
+    for outcome in outcomes {
+        table[outcome.x][outcome.o].add(outcome, Player::X);
+        table[outcome.o][outcome.x].add(outcome, Player::O);
+    }
+    table
+}
+
+/// Standings, cross table, first-move advantage and average game length.
+pub fn report(names: &[String], outcomes: &[GameOutcome]) -> String {
+    let table = cross_table(names.len(), outcomes);
+    let totals: Vec<Score> = table
+        .iter()
+        .map(|row| {
+            row.iter().fold(Score::default(), |sum, s| Score {
+                wins: sum.wins + s.wins,
+                draws: sum.draws + s.draws,
+                losses: sum.losses + s.losses,
+            })
+        })
+        .collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(5);
+
+    let mut text = format!(
+        "{:<width$} {:>5} {:>5} {:>5} {:>7}\n",
+        "Agent", "W", "D", "L", "Points"
+    );
+    let mut order: Vec<usize> = (0..names.len()).collect();
+    order.sort_by(|&a, &b| totals[b].points().total_cmp(&totals[a].points()));
+    for &i in &order {
+        let s = totals[i];
+        text.push_str(&format!(
+            "{:<width$} {:>5} {:>5} {:>5} {:>7.1}\n",
+            names[i],
+            s.wins,
+            s.draws,
+            s.losses,
+            s.points()
+        ));
+    }
+
+    // Each cross-table column is as wide as its header
+    let column = |j: usize| names[j].len().max(5);
+    text.push_str(&format!("\n{:<width$}", "W-D-L"));
+    for (j, name) in names.iter().enumerate() {
+        text.push_str(&format!(" {:>w$}", name, w = column(j)));
+    }
+    text.push('\n');
+    for (i, row) in table.iter().enumerate() {
+        text.push_str(&format!("{:<width$}", names[i]));
+        for (j, s) in row.iter().enumerate() {
+            let cell = if i == j {
+                "-".to_string()
+            } else {
+                format!("{}-{}-{}", s.wins, s.draws, s.losses)
+            };
+            text.push_str(&format!(" {:>w$}", cell, w = column(j)));
+        }
+        text.push('\n');
+    }
+
+    let games = outcomes.len().max(1) as f64;
+    let count = |winner| outcomes.iter().filter(|o| o.winner == winner).count();
+    let percent = |n: usize| 100.0 * n as f64 / games;
+    let (x_wins, o_wins, draws) = (count(Some(Player::X)), count(Some(Player::O)), count(None));
+    text.push_str(&format!(
+        "\nFirst mover: X won {} ({:.1}%), O won {} ({:.1}%), drawn {} ({:.1}%)\n",
+        x_wins,
+        percent(x_wins),
+        o_wins,
+        percent(o_wins),
+        draws,
+        percent(draws)
+    ));
+    let moves: usize = outcomes.iter().map(|o| o.length).sum();
+    text.push_str(&format!(
+        "Average game length: {:.2} moves over {} games\n",
+        moves as f64 / games,
+        outcomes.len()
+    ));
+    text
+}
+
+/// Run a tournament from command-line arguments:
+/// `[--games <n>] [--seed <n>] <agent> <agent> [<agent> ...]`.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut games = 10;
+    let mut seed = 0;
+    let mut specs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("{} needs a number", flag))
+        };
+        match arg.as_str() {
+            "--games" => games = value("--games")? as usize,
+            "--seed" => seed = value("--seed")?,
+            spec => specs.push(spec.to_string()),
+        }
+    }
+    if specs.len() < 2 {
+        return Err("a tournament needs at least two agents".to_string());
+    }
+
+    // Every entrant's randomness comes from the tournament seed, so a rerun with the
+    // same seed replays the same games.
+    let mut rng = Rng::new(seed);
+    let mut agents = specs
+        .iter()
+        .map(|spec| agent_from_spec(spec, rng.next_u64()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let outcomes = round_robin(&mut agents, games);
+    print!("{}", report(&specs, &outcomes));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents(specs: &[&str], seed: u64) -> Vec<Box<dyn Agent>> {
+        let mut rng = Rng::new(seed);
+        specs
+            .iter()
+            .map(|spec| agent_from_spec(spec, rng.next_u64()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_minimax_draws_itself() {
+        let mut a = agents(&["minimax", "minimax"], 0);
+        let (x, o) = a.split_at_mut(1);
+        assert_eq!(play_game(x[0].as_mut(), o[0].as_mut()), (None, 9));
+    }
+
+    #[test]
+    fn test_round_robin_alternates_and_is_reproducible() {
+        let specs = ["minimax", "heuristic", "random"];
+        let outcomes = round_robin(&mut agents(&specs, 7), 4);
+        assert_eq!(outcomes.len(), 3 * 4);
+        assert_eq!((outcomes[0].x, outcomes[0].o), (0, 1));
+        assert_eq!((outcomes[1].x, outcomes[1].o), (1, 0));
+        assert_eq!(round_robin(&mut agents(&specs, 7), 4), outcomes);
+
+        let table = cross_table(specs.len(), &outcomes);
+        assert_eq!(table[0][1].losses + table[0][2].losses, 0);
+        assert_eq!(table[1][0].wins, table[0][1].losses);
+    }
+
+    #[test]
+    fn test_report() {
+        let names = ["a".to_string(), "b".to_string()];
+        let outcomes = [
+            GameOutcome {
+                x: 0,
+                o: 1,
+                winner: Some(Player::X),
+                length: 5,
+            },
+            GameOutcome {
+                x: 1,
+                o: 0,
+                winner: None,
+                length: 9,
+            },
+        ];
+        let text = report(&names, &outcomes);
+        assert!(
+            text.contains("a         1     1     0     1.5\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("a         - 1-1-0\n"), "{}", text);
+        assert!(text.contains("X won 1 (50.0%), O won 0 (0.0%), drawn 1 (50.0%)"));
+        assert!(text.contains("Average game length: 7.00 moves over 2 games"));
+    }
+}
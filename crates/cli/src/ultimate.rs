@@ -0,0 +1,156 @@
+// Console interface for ultimate tic-tac-toe.
+
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::ultimate::{UltimateEngine, UltimatePosition};
+use rust_tac_toe_engine::{InvalidGameMoveError, Player};
+
+pub fn run() {
+    console::run(&mut UltimateEngine::new());
+}
+
+impl ConsoleGame for UltimateEngine {
+    fn board_as_string(&self) -> String {
+        board_as_string(self)
+    }
+
+    fn result(&self) -> Option<String> {
+        console::status_result(&self.status)
+    }
+
+    fn turn(&self) -> String {
+        let board = match self.active_board {
+            Some(board) => format!("You must play in board {}", board.to_index()),
+            None => "You may play in any open board".to_string(),
+        };
+        format!(
+            "{}\n{}",
+            console::current_player(self.current_player),
+            board
+        )
+    }
+
+    fn move_prompt(&self) -> String {
+        "Enter your move as '<board> <cell>' (each 0-8)".to_string()
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let Some(pos) = parse_position(input) else {
+            println!("Invalid input. Please enter a board and a cell, both between 0 and 8.");
+            return false;
+        };
+
+        if let Err(e) = self.play_move(pos) {
+            match e {
+                InvalidGameMoveError::GameAlreadyWon => {
+                    println!("Error: That board is already finished. Try another.");
+                }
+                InvalidGameMoveError::SpaceOccupied => {
+                    println!("Error: That space is already occupied. Try another.");
+                }
+                InvalidGameMoveError::WrongBoard => {
+                    let board = self.active_board.map_or(0, |b| b.to_index());
+                    println!("Error: You must play in board {}.", board);
+                }
+                InvalidGameMoveError::OutOfBounds
+                | InvalidGameMoveError::Floating
+                | InvalidGameMoveError::WrongMoveKind
+                | InvalidGameMoveError::NotInCycle
+                | InvalidGameMoveError::Forbidden => {
+                    println!("Invalid position. Please enter numbers between 0 and 8.");
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        UltimateEngine::reset(self);
+    }
+}
+
+/// Render the nine small boards as a 9x9 grid, with empty cells shown as dots.
+pub fn board_as_string(engine: &UltimateEngine) -> String {
+    let get_char = |board: usize, cell: usize| match engine.boards[board].board[cell] {
+        Some(Player::X) => "X",
+        Some(Player::O) => "O",
+        None => ".",
+    };
+
+    let mut board_str = String::new();
+    for board_row in 0..3 {
+        for cell_row in 0..3 {
+            let row: Vec<String> = (0..3)
+                .map(|board_col| {
+                    let board = board_row * 3 + board_col;
+                    let cell = cell_row * 3;
+                    format!(
+                        " {} {} {} ",
+                        get_char(board, cell),
+                        get_char(board, cell + 1),
+                        get_char(board, cell + 2)
+                    )
+                })
+                .collect();
+            board_str.push_str(&row.join("|"));
+            board_str.push('\n');
+        }
+
+        // Print horizontal divider between board rows (but not after the last row)
+        if board_row < 2 {
+            board_str.push_str("-------+-------+-------\n");
+        }
+    }
+    board_str
+}
+
+/// Parse "4 2" (or the shorthand "42") into board 4, cell 2.
+fn parse_position(input: &str) -> Option<UltimatePosition> {
+    let digits: Vec<u8> = match input.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [board, cell] => vec![board.parse().ok()?, cell.parse().ok()?],
+        [both] if both.len() == 2 => both
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    UltimatePosition::new(digits[0], digits[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    #[test]
+    fn test_print_board() {
+        let mut engine = UltimateEngine::new();
+        handle_input(&mut engine, "4 0");
+        handle_input(&mut engine, "08");
+
+        let board_str = board_as_string(&engine);
+        let expected_str = concat!(
+            " . . . | . . . | . . . \n",
+            " . . . | . . . | . . . \n",
+            " . . O | . . . | . . . \n",
+            "-------+-------+-------\n",
+            " . . . | X . . | . . . \n",
+            " . . . | . . . | . . . \n",
+            " . . . | . . . | . . . \n",
+            "-------+-------+-------\n",
+            " . . . | . . . | . . . \n",
+            " . . . | . . . | . . . \n",
+            " . . . | . . . | . . . \n",
+        );
+        assert_eq!(board_str, expected_str);
+    }
+
+    #[test]
+    fn test_handle_input_wrong_board() {
+        let mut engine = UltimateEngine::new();
+        assert!(handle_input(&mut engine, "4 2"));
+        assert!(!handle_input(&mut engine, "5 0"));
+        assert!(!handle_input(&mut engine, "9 0"));
+        assert!(handle_input(&mut engine, "2 4"));
+    }
+}
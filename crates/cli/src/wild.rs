@@ -0,0 +1,130 @@
+// Console interface for wild tic-tac-toe, where each move places an X or an O.
+
+use crate::cli::cells_as_string;
+use crate::console::{self, ConsoleGame};
+use rust_tac_toe_engine::wild::{WildGame, WildMove};
+use rust_tac_toe_engine::{GameStatus, InvalidGameMoveError, Player};
+
+pub fn run() {
+    println!("Wild rules: place an X or an O each turn. Whoever completes a line wins.");
+    console::run(&mut WildGame::default());
+}
+
+impl ConsoleGame for WildGame {
+    const CAN_UNDO: bool = true;
+
+    fn board_as_string(&self) -> String {
+        cells_as_string(self.board())
+    }
+
+    fn result(&self) -> Option<String> {
+        match *self.status() {
+            GameStatus::Win { player, line: _ } => {
+                Some(format!("Game over! Winner: {}", player_name(player)))
+            }
+            GameStatus::Draw => Some("Game over! It's a draw!".to_string()),
+            GameStatus::Ongoing => None,
+        }
+    }
+
+    fn turn(&self) -> String {
+        format!("Current player: {}", player_name(self.current_player()))
+    }
+
+    fn move_prompt(&self) -> String {
+        "Enter your move as a cell and a symbol (e.g. '4x')".to_string()
+    }
+
+    fn play(&mut self, input: &str) -> bool {
+        let Some(mv) = parse_move(input) else {
+            println!("Invalid input. Please enter a cell between 0 and 8 followed by 'x' or 'o'.");
+            return false;
+        };
+
+        if let Err(e) = self.play_move(mv) {
+            match e {
+                InvalidGameMoveError::GameAlreadyWon => {
+                    println!("Error: The game is already won! Press 'r' to reset.");
+                }
+                InvalidGameMoveError::SpaceOccupied => {
+                    println!("Error: That space is already occupied. Try another.");
+                }
+                InvalidGameMoveError::OutOfBounds
+                | InvalidGameMoveError::WrongBoard
+                | InvalidGameMoveError::Floating
+                | InvalidGameMoveError::WrongMoveKind
+                | InvalidGameMoveError::NotInCycle
+                | InvalidGameMoveError::Forbidden => {
+                    println!("Invalid position. Please enter a number between 0 and 8.");
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        WildGame::reset(self);
+    }
+
+    fn undo(&mut self) -> bool {
+        WildGame::undo(self).is_some()
+    }
+}
+
+/// Both players may place either symbol, so they go by their turn order instead.
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::X => "Player 1",
+        Player::O => "Player 2",
+    }
+}
+
+/// Parse "4x" or "4 o" into cell 4 with the given symbol, ignoring case.
+fn parse_move(input: &str) -> Option<WildMove> {
+    let input = input.to_lowercase();
+    let (cell, symbol) = input.split_at_checked(1)?;
+    let symbol = match symbol.trim() {
+        "x" => Player::X,
+        "o" => Player::O,
+        _ => return None,
+    };
+    WildMove::new(cell.parse().ok()?, symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::handle_input;
+
+    #[test]
+    fn test_parse_move() {
+        assert_eq!(parse_move("4x"), WildMove::new(4, Player::X));
+        assert_eq!(parse_move("0 O"), WildMove::new(0, Player::O));
+        assert_eq!(parse_move("9x"), None);
+        assert_eq!(parse_move("4"), None);
+        assert_eq!(parse_move("4y"), None);
+    }
+
+    #[test]
+    fn test_handle_input_wins_for_mover() {
+        let mut game = WildGame::default();
+        for input in ["0o", "1o", "4x"] {
+            assert!(handle_input(&mut game, input));
+        }
+        assert!(!handle_input(&mut game, "4o"));
+        // The second player completes the first player's row of O's
+        assert!(handle_input(&mut game, "2o"));
+        assert_eq!(
+            *game.status(),
+            GameStatus::Win {
+                player: Player::O,
+                line: [0, 1, 2]
+            }
+        );
+        assert_eq!(
+            cells_as_string(game.board()),
+            " O | O | O \n-----------\n   | X |   \n-----------\n   |   |   \n"
+        );
+    }
+}
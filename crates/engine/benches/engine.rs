@@ -0,0 +1,220 @@
+//! Throughput of the classic engine.
+//!
+//! `previous` is the engine and search as they were before the bitboard backend: the
+//! same fields and move log, but every move rescans the `[Option<Player>; 9]` board
+//! against the table of winning lines, and the search copies the whole engine at
+//! every node. It is kept here as the baseline to measure against.
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use rust_tac_toe_engine::rng::Rng;
+use rust_tac_toe_engine::{GameEngine, GameStatus, Player, Position, ai, zobrist};
+
+mod previous {
+    use super::*;
+
+    const WINNING_COMBINATIONS: [[usize; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8],
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8],
+        [0, 4, 8],
+        [2, 4, 6],
+    ];
+
+    #[derive(Clone, Copy)]
+    pub struct Engine {
+        pub board: [Option<Player>; 9],
+        pub current_player: Player,
+        pub status: GameStatus,
+        moves: [u8; 9],
+        len: u8,
+        end: u8,
+        hash: u64,
+    }
+
+    impl Engine {
+        pub fn new() -> Self {
+            Self {
+                board: [None; 9],
+                current_player: Player::X,
+                status: GameStatus::Ongoing,
+                moves: [0; 9],
+                len: 0,
+                end: 0,
+                hash: 0,
+            }
+        }
+
+        pub fn play_move(&mut self, pos: Position) -> Result<(), ()> {
+            self.validate_move(pos)?;
+            self.end = self.len;
+            self.board[pos.to_index()] = Some(self.current_player);
+            self.hash ^= zobrist::key(self.current_player, pos);
+            self.moves[self.len as usize] = pos.to_index() as u8;
+            self.len += 1;
+            self.end = self.end.max(self.len);
+            self.status = self.calculate_status();
+            self.current_player = self.current_player.next();
+            Ok(())
+        }
+
+        pub fn validate_move(&self, pos: Position) -> Result<(), ()> {
+            if self.status != GameStatus::Ongoing || self.board[pos.to_index()].is_some() {
+                return Err(());
+            }
+            Ok(())
+        }
+
+        pub fn calculate_status(&self) -> GameStatus {
+            for [a, b, c] in WINNING_COMBINATIONS.iter() {
+                if let (Some(p1), Some(p2), Some(p3)) =
+                    (self.board[*a], self.board[*b], self.board[*c])
+                    && p1 == p2
+                    && p2 == p3
+                {
+                    return GameStatus::Win {
+                        player: p1,
+                        line: [*a, *b, *c],
+                    };
+                }
+            }
+            if self.board.iter().all(|&p| p.is_some()) {
+                return GameStatus::Draw;
+            }
+            GameStatus::Ongoing
+        }
+
+        pub fn moves_played(&self) -> usize {
+            self.len as usize
+        }
+    }
+
+    pub fn best_move(engine: &Engine) -> Option<Position> {
+        let mut best: Option<(Position, i32)> = None;
+        let mut alpha = -i32::MAX;
+        for pos in legal_moves(engine) {
+            let mut child = *engine;
+            let _ = child.play_move(pos);
+            let score = -negamax(&child, 1, -i32::MAX, -alpha);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((pos, score));
+            }
+            alpha = alpha.max(score);
+        }
+        best.map(|(pos, _)| pos)
+    }
+
+    fn negamax(engine: &Engine, depth: i32, mut alpha: i32, beta: i32) -> i32 {
+        match engine.status {
+            GameStatus::Win { .. } => return depth - 10,
+            GameStatus::Draw => return 0,
+            GameStatus::Ongoing => {}
+        }
+        let mut best = -i32::MAX;
+        for pos in legal_moves(engine) {
+            let mut child = *engine;
+            let _ = child.play_move(pos);
+            let score = -negamax(&child, depth + 1, -beta, -alpha);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn legal_moves(engine: &Engine) -> Vec<Position> {
+        (0..9)
+            .filter_map(Position::new)
+            .filter(|&pos| engine.validate_move(pos).is_ok())
+            .collect()
+    }
+}
+
+/// Plays a random game with either engine. Returns the number of moves played.
+macro_rules! random_game {
+    ($engine:expr, $rng:expr) => {{
+        let mut engine = $engine;
+        let mut moves = Vec::with_capacity(9);
+        while engine.status == GameStatus::Ongoing {
+            moves.clear();
+            moves.extend(
+                (0..9)
+                    .filter_map(Position::new)
+                    .filter(|&p| engine.validate_move(p).is_ok()),
+            );
+            let _ = engine.play_move(moves[$rng.gen_range(moves.len())]);
+        }
+        engine
+    }};
+}
+
+/// Positions from a spread of random games, from empty boards to full ones.
+fn sample_positions() -> Vec<Vec<Position>> {
+    let mut rng = Rng::new(1);
+    (0..64)
+        .map(|_| {
+            let engine = random_game!(GameEngine::new(), rng);
+            let history = engine.history();
+            history[..rng.gen_range(history.len())].to_vec()
+        })
+        .collect()
+}
+
+/// A perfect move for every sample position. This is the search behind the perfect
+/// AI, the tablebase checks and the protocol bot.
+fn bench_perfect_play(c: &mut Criterion) {
+    let samples = sample_positions();
+    let setup = |moves: &[Position]| {
+        let mut engine = GameEngine::new();
+        let mut old = previous::Engine::new();
+        for &pos in moves {
+            let _ = engine.play_move(pos);
+            let _ = old.play_move(pos);
+        }
+        (engine, old)
+    };
+    let (engines, olds): (Vec<_>, Vec<_>) = samples.iter().map(|m| setup(m)).unzip();
+
+    let mut group = c.benchmark_group("perfect_play");
+    group.bench_function(BenchmarkId::new("previous", samples.len()), |b| {
+        b.iter(|| {
+            black_box(&olds)
+                .iter()
+                .filter_map(previous::best_move)
+                .count()
+        })
+    });
+    group.bench_function(BenchmarkId::new("bitboard", samples.len()), |b| {
+        b.iter(|| black_box(&engines).iter().filter_map(ai::best_move).count())
+    });
+    group.finish();
+}
+
+fn bench_random_games(c: &mut Criterion) {
+    const GAMES: usize = 1_000;
+    let mut group = c.benchmark_group("random_games");
+    group.bench_function(BenchmarkId::new("previous", GAMES), |b| {
+        let mut rng = Rng::new(7);
+        b.iter(|| {
+            (0..GAMES)
+                .map(|_| random_game!(previous::Engine::new(), rng).moves_played())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function(BenchmarkId::new("bitboard", GAMES), |b| {
+        let mut rng = Rng::new(7);
+        b.iter(|| {
+            (0..GAMES)
+                .map(|_| random_game!(GameEngine::new(), rng).history().len())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_perfect_play, bench_random_games);
+criterion_main!(benches);
@@ -0,0 +1,121 @@
+//! Players seated on either side of a game: people at a front end, built-in AIs and
+//! remote engines, all asked for their moves the same way.
+
+use crate::ai::{AiPlayer, heuristic_move};
+use crate::{GameEngine, GameStatus, Player, Position};
+
+/// Chooses moves for whichever side it is seated on.
+pub trait Agent {
+    /// Picks a move for `engine.current_player`, or `None` if it has none to offer
+    /// (the game is over, or a remote player has gone away).
+    fn choose_move(&mut self, engine: &GameEngine) -> Option<Position>;
+
+    /// Whether the agent's moves come from the front end's input, handed over through
+    /// [`Agent::input`], so the front end should wait for and accept input.
+    fn takes_input(&self) -> bool {
+        false
+    }
+
+    /// Hands over a move picked through the front end, such as a click or a typed
+    /// position. Agents that pick their own moves ignore it.
+    fn input(&mut self, _pos: Position) {}
+}
+
+/// A person at a front end. It offers the last move handed over through
+/// [`Agent::input`], once, and has nothing to offer until the next one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HumanPlayer {
+    pending: Option<Position>,
+}
+
+impl Agent for HumanPlayer {
+    fn choose_move(&mut self, _engine: &GameEngine) -> Option<Position> {
+        self.pending.take()
+    }
+
+    fn takes_input(&self) -> bool {
+        true
+    }
+
+    fn input(&mut self, pos: Position) {
+        self.pending = Some(pos);
+    }
+}
+
+impl Agent for AiPlayer {
+    fn choose_move(&mut self, engine: &GameEngine) -> Option<Position> {
+        AiPlayer::choose_move(self, engine)
+    }
+}
+
+/// Plays by the simple rules of [`heuristic_move`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicPlayer;
+
+impl Agent for HeuristicPlayer {
+    fn choose_move(&mut self, engine: &GameEngine) -> Option<Position> {
+        heuristic_move(engine)
+    }
+}
+
+/// Plays a game from the start with `x` and `o` moving in turn.
+///
+/// If an agent offers no move or an illegal one the game stops there, so the returned
+/// game is still ongoing with the offending agent to move.
+pub fn play_game(x: &mut dyn Agent, o: &mut dyn Agent) -> GameEngine {
+    let mut engine = GameEngine::new();
+    while engine.status == GameStatus::Ongoing {
+        let choice = match engine.current_player {
+            Player::X => x.choose_move(&engine),
+            Player::O => o.choose_move(&engine),
+        };
+        let played = choice.is_some_and(|pos| engine.play_move(pos).is_ok());
+        if !played {
+            break;
+        }
+    }
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Difficulty;
+
+    /// Always answers with the same square, which soon becomes illegal.
+    struct Stubborn;
+
+    impl Agent for Stubborn {
+        fn choose_move(&mut self, _engine: &GameEngine) -> Option<Position> {
+            Position::new(4)
+        }
+    }
+
+    #[test]
+    fn test_agents_play_either_side() {
+        let mut perfect = AiPlayer::new(Difficulty::Perfect, 0);
+        let engine = play_game(&mut HeuristicPlayer, &mut perfect);
+        assert_eq!(engine.status, GameStatus::Draw);
+        let engine = play_game(&mut perfect, &mut HeuristicPlayer);
+        assert_eq!(engine.status, GameStatus::Draw);
+    }
+
+    #[test]
+    fn test_human_plays_its_input_once() {
+        let engine = GameEngine::new();
+        let mut human = HumanPlayer::default();
+        assert!(human.takes_input() && !HeuristicPlayer.takes_input());
+        assert_eq!(human.choose_move(&engine), None);
+        human.input(Position::new(4).unwrap());
+        assert_eq!(human.choose_move(&engine), Position::new(4));
+        assert_eq!(human.choose_move(&engine), None);
+    }
+
+    #[test]
+    fn test_illegal_move_stops_the_game() {
+        let engine = play_game(&mut HeuristicPlayer, &mut Stubborn);
+        assert_eq!(engine.status, GameStatus::Ongoing);
+        assert_eq!(engine.current_player, Player::O);
+        assert_eq!(engine.history().len(), 1);
+    }
+}
@@ -0,0 +1,390 @@
+//! Computer opponent for the classic 3x3 game.
+
+use crate::rng::Rng;
+use crate::rules::RuleSet;
+use crate::{FULL_BOARD, GameEngine, GameStatus, Player, Position, cells, has_line};
+
+/// Score of a won position before it is discounted by search depth.
+const WIN_SCORE: i32 = 10;
+
+/// Returns the optimal move for `engine.current_player`, or `None` if the game is over.
+///
+/// Uses a full minimax search with alpha-beta pruning. Faster wins and slower losses
+/// are preferred, and ties are broken by the lowest board index.
+pub fn best_move(engine: &GameEngine) -> Option<Position> {
+    search_best_move(engine, None)
+}
+
+/// Like [`best_move`], but positions more than `max_depth` plies ahead are scored as draws.
+fn search_best_move(engine: &GameEngine, max_depth: Option<u32>) -> Option<Position> {
+    if engine.status != GameStatus::Ongoing {
+        return None;
+    }
+
+    let sides = Sides::of(engine);
+    let mut best: Option<(Position, i32)> = None;
+    let mut alpha = -i32::MAX;
+
+    for cell in cells(sides.empty()) {
+        let score = -negamax(sides.play(cell), 1, max_depth, -i32::MAX, -alpha);
+        if best.is_none_or(|(_, s)| score > s) {
+            best = Some((Position(cell), score));
+        }
+        alpha = alpha.max(score);
+    }
+
+    best.map(|(pos, _)| pos)
+}
+
+/// A position as seen by the side to move: its pieces and the opponent's, as
+/// bitmasks. Searching on these avoids copying whole engines at every node.
+#[derive(Clone, Copy)]
+struct Sides {
+    own: u16,
+    other: u16,
+    rules: RuleSet,
+}
+
+impl Sides {
+    fn of(engine: &GameEngine) -> Self {
+        Self {
+            own: engine.cells_of(engine.current_player),
+            other: engine.cells_of(engine.current_player.next()),
+            rules: engine.rules(),
+        }
+    }
+
+    /// The position after the side to move takes `cell`, seen by the opponent.
+    fn play(self, cell: u8) -> Self {
+        Self {
+            own: self.other,
+            other: self.own | 1 << cell,
+            rules: self.rules,
+        }
+    }
+
+    fn empty(self) -> u16 {
+        !(self.own | self.other) & FULL_BOARD
+    }
+}
+
+/// Scores a position from the point of view of the side to move.
+fn negamax(sides: Sides, depth: u32, max_depth: Option<u32>, mut alpha: i32, beta: i32) -> i32 {
+    // If the previous mover completed a line, the side to move has lost, or under
+    // misère rules has won.
+    if has_line(sides.other) {
+        let lost = depth as i32 - WIN_SCORE;
+        return match sides.rules {
+            RuleSet::Standard => lost,
+            RuleSet::Misere => -lost,
+        };
+    }
+    let empty = sides.empty();
+    if empty == 0 || max_depth.is_some_and(|max| depth >= max) {
+        return 0;
+    }
+
+    let mut best = -i32::MAX;
+    for cell in cells(empty) {
+        let score = -negamax(sides.play(cell), depth + 1, max_depth, -beta, -alpha);
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// Picks a move by simple rules instead of searching: complete a line if possible,
+/// otherwise block the opponent's line, otherwise take the center, a corner, then a side.
+///
+/// Under misère rules it avoids completing its own line and otherwise keeps the same
+/// order of preference.
+pub fn heuristic_move(engine: &GameEngine) -> Option<Position> {
+    if engine.status != GameStatus::Ongoing {
+        return None;
+    }
+    let moves: Vec<Position> = engine.legal_moves().collect();
+    let makes_line = |player: Player, pos: Position| {
+        let mut child = *engine;
+        child.current_player = player;
+        child.play_move(pos).is_ok() && matches!(child.status, GameStatus::Win { .. })
+    };
+    let completes_line =
+        |player: Player| moves.iter().copied().find(|&pos| makes_line(player, pos));
+    let by_preference = |allowed: &dyn Fn(Position) -> bool| {
+        [4, 0, 2, 6, 8, 1, 3, 5, 7]
+            .into_iter()
+            .map(Position)
+            .find(|&pos| moves.contains(&pos) && allowed(pos))
+    };
+
+    match engine.rules() {
+        RuleSet::Standard => completes_line(engine.current_player)
+            .or_else(|| completes_line(engine.current_player.next()))
+            .or_else(|| by_preference(&|_| true)),
+        RuleSet::Misere => by_preference(&|pos| !makes_line(engine.current_player, pos))
+            .or_else(|| by_preference(&|_| true)),
+    }
+}
+
+/// Returns the player that would win with perfect play from `engine`, or `None` for a draw.
+pub fn solve(engine: &GameEngine) -> Option<Player> {
+    match engine.status {
+        GameStatus::Win { player, .. } => return Some(player),
+        GameStatus::Draw => return None,
+        GameStatus::Ongoing => {}
+    }
+    let score = negamax(Sides::of(engine), 0, None, -i32::MAX, i32::MAX);
+    match score.signum() {
+        1 => Some(engine.current_player),
+        -1 => Some(engine.current_player.next()),
+        _ => None,
+    }
+}
+
+/// Preset strengths for the computer opponent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Plays uniformly random legal moves.
+    Random,
+    Easy,
+    Medium,
+    Hard,
+    /// Never makes a mistake; a game against it is at best a draw.
+    Perfect,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Random,
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Perfect,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Random => "random",
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Perfect => "perfect",
+        }
+    }
+
+    /// Parses a difficulty from its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn config(&self) -> AiConfig {
+        let (mistake_probability, max_depth) = match self {
+            Difficulty::Random => (1.0, None),
+            Difficulty::Easy => (0.4, Some(1)),
+            Difficulty::Medium => (0.2, Some(2)),
+            Difficulty::Hard => (0.05, None),
+            Difficulty::Perfect => (0.0, None),
+        };
+        AiConfig {
+            mistake_probability,
+            max_depth,
+        }
+    }
+}
+
+/// Tuning knobs for [`AiPlayer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AiConfig {
+    /// Chance, per move, of playing a random legal move instead of searching.
+    pub mistake_probability: f64,
+    /// Number of plies to look ahead, or `None` to search to the end of the game.
+    pub max_depth: Option<u32>,
+}
+
+/// A computer player whose strength is set by an [`AiConfig`].
+///
+/// All randomness comes from a seeded [`Rng`], so two players built with the same
+/// config and seed choose the same moves.
+#[derive(Debug, Clone)]
+pub struct AiPlayer {
+    pub config: AiConfig,
+    rng: Rng,
+}
+
+impl AiPlayer {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self::with_config(difficulty.config(), seed)
+    }
+
+    pub fn with_config(config: AiConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Picks a move for `engine.current_player`, or `None` if the game is over.
+    pub fn choose_move(&mut self, engine: &GameEngine) -> Option<Position> {
+        if self.rng.gen_bool(self.config.mistake_probability) {
+            let moves: Vec<Position> = engine.legal_moves().collect();
+            return self.rng.choose(&moves);
+        }
+        search_best_move(engine, self.config.max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_takes_immediate_win() {
+        // X: 0, 1  O: 3, 4  -> X completes the top row.
+        let engine = play(&[0, 3, 1, 4]);
+        assert_eq!(best_move(&engine).unwrap().to_index(), 2);
+    }
+
+    #[test]
+    fn test_blocks_opponent_win() {
+        // X: 0, 1  O: 4  -> O must block at 2.
+        let engine = play(&[0, 4, 1]);
+        assert_eq!(best_move(&engine).unwrap().to_index(), 2);
+    }
+
+    #[test]
+    fn test_heuristic_move() {
+        assert_eq!(heuristic_move(&GameEngine::new()), Position::new(4));
+        // X can win at 2 and must also block at 5; winning comes first.
+        assert_eq!(heuristic_move(&play(&[0, 3, 1, 4])), Position::new(2));
+        assert_eq!(heuristic_move(&play(&[0, 4, 1])), Position::new(2));
+        assert_eq!(heuristic_move(&play(&[4])), Position::new(0));
+    }
+
+    #[test]
+    fn test_no_move_when_game_over() {
+        let engine = play(&[0, 3, 1, 4, 2]);
+        assert!(best_move(&engine).is_none());
+    }
+
+    #[test]
+    fn test_perfect_play_draws() {
+        let mut engine = GameEngine::new();
+        while let Some(pos) = best_move(&engine) {
+            engine.play_move(pos).unwrap();
+        }
+        assert_eq!(engine.status, GameStatus::Draw);
+        assert_eq!(solve(&GameEngine::new()), None);
+    }
+
+    #[test]
+    fn test_misere_avoids_lines() {
+        // X: 0, 1; O: 3, 4. Standard X wins at 2, which under misère would lose.
+        let mut engine = GameEngine::with_rules(RuleSet::Misere);
+        for &m in &[0, 3, 1, 4] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        assert_ne!(best_move(&engine), Position::new(2));
+        assert_ne!(heuristic_move(&engine), Position::new(2));
+
+        // Perfect misère play is a draw
+        assert_eq!(solve(&GameEngine::with_rules(RuleSet::Misere)), None);
+        let mut game = GameEngine::with_rules(RuleSet::Misere);
+        while let Some(pos) = best_move(&game) {
+            game.play_move(pos).unwrap();
+        }
+        assert_eq!(game.status, GameStatus::Draw);
+    }
+
+    #[test]
+    fn test_perfect_never_loses_misere() {
+        let mut perfect = AiPlayer::new(Difficulty::Perfect, 0);
+        let mut random = AiPlayer::new(Difficulty::Random, 11);
+        for game in 0..20 {
+            let mut engine = GameEngine::with_rules(RuleSet::Misere);
+            let perfect_side = if game % 2 == 0 { Player::X } else { Player::O };
+            while engine.status == GameStatus::Ongoing {
+                let ai = if engine.current_player == perfect_side {
+                    &mut perfect
+                } else {
+                    &mut random
+                };
+                let pos = ai.choose_move(&engine).unwrap();
+                engine.play_move(pos).unwrap();
+            }
+            assert!(
+                !matches!(engine.status, GameStatus::Win { player, .. } if player != perfect_side),
+                "perfect player lost misère game {}",
+                game
+            );
+        }
+    }
+
+    #[test]
+    fn test_difficulty_from_name() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(Difficulty::from_name(difficulty.name()), Some(difficulty));
+        }
+        assert_eq!(Difficulty::from_name("HARD"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::from_name("impossible"), None);
+    }
+
+    fn self_play(seed: u64, difficulty: Difficulty) -> GameEngine {
+        let mut x = AiPlayer::new(difficulty, seed);
+        let mut o = AiPlayer::new(difficulty, seed.wrapping_add(1));
+        let mut engine = GameEngine::new();
+        while engine.status == GameStatus::Ongoing {
+            let ai = match engine.current_player {
+                Player::X => &mut x,
+                Player::O => &mut o,
+            };
+            let pos = ai.choose_move(&engine).unwrap();
+            engine.play_move(pos).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_seeded_games_are_reproducible() {
+        for seed in 0..20 {
+            let a = self_play(seed, Difficulty::Easy);
+            let b = self_play(seed, Difficulty::Easy);
+            assert_eq!(a.board, b.board);
+        }
+    }
+
+    #[test]
+    fn test_perfect_never_loses_to_random() {
+        for seed in 0..50 {
+            let mut perfect = AiPlayer::new(Difficulty::Perfect, seed);
+            let mut random = AiPlayer::new(Difficulty::Random, seed);
+            let mut engine = GameEngine::new();
+            while engine.status == GameStatus::Ongoing {
+                let ai = match engine.current_player {
+                    Player::X => &mut random,
+                    Player::O => &mut perfect,
+                };
+                engine.play_move(ai.choose_move(&engine).unwrap()).unwrap();
+            }
+            assert!(!matches!(
+                engine.status,
+                GameStatus::Win {
+                    player: Player::X,
+                    ..
+                }
+            ));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Coaching for the side to move: which moves win, which block, and which fork.
+//!
+//! A fork creates two winning threats at once. The opponent can only block one, so a
+//! fork wins next turn unless the opponent can win first. None of this applies under
+//! misère rules, where completing a line loses.
+
+use crate::{FULL_BOARD, GameEngine, GameStatus, Position, WINNING_LINES, cells};
+
+/// Notable moves for the side to move, each list in board order.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct Hints {
+    /// Moves that complete a line and win at once.
+    pub wins: Vec<Position>,
+    /// Moves that take a cell where the opponent would complete a line next turn.
+    pub blocks: Vec<Position>,
+    /// Moves that create two or more winning threats at once.
+    pub forks: Vec<Position>,
+}
+
+impl Hints {
+    pub fn is_empty(&self) -> bool {
+        self.wins.is_empty() && self.blocks.is_empty() && self.forks.is_empty()
+    }
+}
+
+/// Empty cells that would complete a line for the player holding `own`.
+fn threats(own: u16, empty: u16) -> u16 {
+    WINNING_LINES
+        .iter()
+        .filter(|&&(mask, _)| (own & mask).count_ones() == 2)
+        .fold(0, |cells, &(mask, _)| cells | (empty & mask))
+}
+
+fn positions(mask: u16) -> Vec<Position> {
+    cells(mask).map(Position).collect()
+}
+
+impl GameEngine {
+    /// Winning, blocking and fork-creating moves for the current player. Empty once the
+    /// game is over, and for misère games.
+    pub fn hints(&self) -> Hints {
+        if self.status != GameStatus::Ongoing || !self.rules().is_standard() {
+            return Hints::default();
+        }
+        let own = self.cells_of(self.current_player);
+        let other = self.cells_of(self.current_player.next());
+        let empty = !(own | other) & FULL_BOARD;
+
+        let forks = cells(empty)
+            .filter(|&cell| {
+                let bit = 1 << cell;
+                threats(own | bit, empty & !bit).count_ones() >= 2
+            })
+            .fold(0, |forks, cell| forks | 1 << cell);
+
+        Hints {
+            wins: positions(threats(own, empty)),
+            blocks: positions(threats(other, empty)),
+            forks: positions(forks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position(m)).unwrap();
+        }
+        engine
+    }
+
+    fn indices(positions: &[Position]) -> Vec<usize> {
+        positions.iter().map(Position::to_index).collect()
+    }
+
+    #[test]
+    fn test_wins_and_blocks() {
+        // X: 0, 1; O: 3, 4. X to move can win at 2 and must otherwise block at 5.
+        let hints = play(&[0, 3, 1, 4]).hints();
+        assert_eq!(indices(&hints.wins), [2]);
+        assert_eq!(indices(&hints.blocks), [5]);
+
+        // O to move has nothing to win but must block X's top row
+        let hints = play(&[0, 4, 1]).hints();
+        assert!(hints.wins.is_empty());
+        assert_eq!(indices(&hints.blocks), [2]);
+    }
+
+    #[test]
+    fn test_forks() {
+        // X in opposite corners, O in the centre and on top. X must block at 7, and
+        // 6 would threaten both the left column and the bottom row.
+        let hints = play(&[0, 4, 8, 1]).hints();
+        assert_eq!(indices(&hints.blocks), [7]);
+        assert_eq!(indices(&hints.forks), [6]);
+
+        assert!(GameEngine::new().hints().is_empty());
+        assert!(play(&[0, 3, 1, 4, 2]).hints().is_empty());
+    }
+}
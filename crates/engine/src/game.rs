@@ -0,0 +1,201 @@
+//! A game played under any [`Rules`], with turn order, undo and redo.
+//!
+//! `Game<RuleSet>` plays the classic game, but [`GameEngine`](crate::GameEngine) is
+//! the one the rest of the crate uses: it keeps its own bitboard history for search
+//! and shares only [`RuleSet`](crate::rules::RuleSet)'s validation and status.
+
+use crate::rules::Rules;
+use crate::{GameStatus, InvalidGameMoveError, Player};
+
+/// Serializes like [`GameEngine`](crate::GameEngine): the board, the player to move
+/// and the status.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(
+    feature = "wasm",
+    serde(
+        rename_all = "camelCase",
+        bound(serialize = "R::Board: serde::Serialize, R::Line: serde::Serialize")
+    )
+)]
+pub struct Game<R: Rules> {
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    rules: R,
+    board: R::Board,
+    current_player: Player,
+    status: GameStatus<R::Line>,
+    /// Every move played so far with the board it was played on, so undo is a pop.
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    played: Vec<(R::Move, R::Board)>,
+    /// Undone moves, most recently undone last.
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    undone: Vec<R::Move>,
+}
+
+impl<R: Rules + Default> Default for Game<R> {
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+impl<R: Rules> Game<R> {
+    pub fn new(rules: R) -> Self {
+        let board = rules.new_board();
+        let status = rules.status(&board);
+        Self {
+            rules,
+            board,
+            current_player: Player::X,
+            status,
+            played: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Starts a new game under the same rules.
+    pub fn reset(&mut self) {
+        self.board = self.rules.new_board();
+        self.status = self.rules.status(&self.board);
+        self.current_player = Player::X;
+        self.played.clear();
+        self.undone.clear();
+    }
+
+    pub fn rules(&self) -> &R {
+        &self.rules
+    }
+
+    pub fn board(&self) -> &R::Board {
+        &self.board
+    }
+
+    pub fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    pub fn status(&self) -> &GameStatus<R::Line> {
+        &self.status
+    }
+
+    pub fn validate_move(&self, mv: R::Move) -> Result<(), InvalidGameMoveError> {
+        if self.status != GameStatus::Ongoing {
+            return Err(InvalidGameMoveError::GameAlreadyWon);
+        }
+        self.rules
+            .validate_move(&self.board, self.current_player, mv)
+    }
+
+    /// The moves the current player may play. Empty once the game is over.
+    pub fn legal_moves(&self) -> Vec<R::Move> {
+        if self.status != GameStatus::Ongoing {
+            return Vec::new();
+        }
+        self.rules.legal_moves(&self.board, self.current_player)
+    }
+
+    pub fn play_move(&mut self, mv: R::Move) -> Result<(), InvalidGameMoveError> {
+        self.validate_move(mv)?;
+
+        // A new move discards any undone moves
+        self.undone.clear();
+        self.apply_move(mv);
+        Ok(())
+    }
+
+    fn apply_move(&mut self, mv: R::Move) {
+        self.played.push((mv, self.board.clone()));
+        self.rules
+            .apply_move(&mut self.board, self.current_player, mv);
+        self.status = self
+            .rules
+            .status_after(&self.board, self.current_player, mv);
+        self.current_player = self.current_player.next();
+    }
+
+    /// Takes back the last move, returning it, or `None` if no moves were played.
+    pub fn undo(&mut self) -> Option<R::Move> {
+        let (mv, board) = self.played.pop()?;
+        self.board = board;
+        // Moves are only played while the game is ongoing
+        self.status = GameStatus::Ongoing;
+        self.current_player = self.current_player.next();
+        self.undone.push(mv);
+        Some(mv)
+    }
+
+    /// Replays the last undone move, returning it, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<R::Move> {
+        let mv = self.undone.pop()?;
+        self.apply_move(mv);
+        Some(mv)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.played.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The moves currently on the board, in the order they were played.
+    pub fn history(&self) -> Vec<R::Move> {
+        self.played.iter().map(|(mv, _)| *mv).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::rules::RuleSet;
+    use crate::{GameEngine, Position};
+
+    #[test]
+    fn test_follows_classic_engine() {
+        let mut rng = Rng::new(3);
+        for rules in RuleSet::ALL {
+            for _ in 0..50 {
+                let mut game = Game::new(rules);
+                let mut engine = GameEngine::with_rules(rules);
+                while engine.status == GameStatus::Ongoing {
+                    let moves = game.legal_moves();
+                    assert_eq!(moves, engine.legal_moves().collect::<Vec<_>>());
+                    let pos = moves[rng.gen_range(moves.len())];
+                    game.play_move(pos).unwrap();
+                    engine.play_move(pos).unwrap();
+                    assert_eq!(*game.board(), engine.board);
+                    assert_eq!(*game.status(), engine.status);
+                    assert_eq!(game.current_player(), engine.current_player);
+                }
+                assert_eq!(game.history(), engine.history());
+            }
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut game = Game::new(RuleSet::Standard);
+        for m in [0, 3, 1, 4, 2] {
+            game.play_move(Position(m)).unwrap();
+        }
+        assert!(matches!(game.status(), GameStatus::Win { .. }));
+        assert_eq!(
+            game.validate_move(Position(8)),
+            Err(InvalidGameMoveError::GameAlreadyWon)
+        );
+
+        assert_eq!(game.undo(), Some(Position(2)));
+        assert_eq!(*game.status(), GameStatus::Ongoing);
+        assert_eq!(game.current_player(), Player::X);
+        assert_eq!(game.redo(), Some(Position(2)));
+        assert!(matches!(game.status(), GameStatus::Win { .. }));
+
+        game.undo();
+        game.play_move(Position(8)).unwrap();
+        assert!(!game.can_redo());
+        game.reset();
+        assert!(!game.can_undo());
+        assert_eq!(game.legal_moves().len(), 9);
+    }
+}
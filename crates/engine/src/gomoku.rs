@@ -0,0 +1,482 @@
+//! Gomoku rule sets and openings for the five-in-a-row [`MnkEngine`].
+//!
+//! Freestyle Gomoku counts five or more in a row, standard Gomoku exactly five.
+//! Renju evens out the first player's advantage: black (X, who moves first) needs
+//! exactly five and may not make an overline, two fours or two threes with one move,
+//! while white wins with five or more.
+//!
+//! Swap2 evens it out further by deciding colours after the opening: the first player
+//! places three stones, then the second player takes a colour or places two more and
+//! leaves the choice to the first player.
+
+use crate::mnk::{DIRECTIONS, MnkEngine};
+use crate::{InvalidGameMoveError, Player};
+
+/// Which runs win, and which moves black may not play.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub enum GomokuRules {
+    /// Five or more in a row wins.
+    #[default]
+    Freestyle,
+    /// Exactly five in a row wins; an overline does not.
+    Standard,
+    /// Black needs exactly five and may not play forbidden moves; white wins with
+    /// five or more.
+    Renju,
+}
+
+impl GomokuRules {
+    pub const ALL: [GomokuRules; 3] = [
+        GomokuRules::Freestyle,
+        GomokuRules::Standard,
+        GomokuRules::Renju,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GomokuRules::Freestyle => "freestyle",
+            GomokuRules::Standard => "standard",
+            GomokuRules::Renju => "renju",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rules| rules.name() == name)
+    }
+
+    /// Whether a run of `len` stones wins for `player` on a board where `k` in a row
+    /// is needed.
+    pub fn wins(&self, player: Player, len: usize, k: usize) -> bool {
+        match (self, player) {
+            (GomokuRules::Freestyle, _) | (GomokuRules::Renju, Player::O) => len >= k,
+            (GomokuRules::Standard, _) | (GomokuRules::Renju, Player::X) => len == k,
+        }
+    }
+}
+
+/// How deep the check that a three can really become a straight four may recurse.
+/// Deeper chains are taken to be threes.
+const FORBIDDEN_DEPTH: usize = 3;
+
+/// Whether black may not play at the empty cell `index` under Renju rules.
+///
+/// A move that makes exactly `k` in a row is always allowed. Otherwise it is forbidden
+/// if it makes an overline, two or more fours, or two or more threes. A four is a
+/// run one stone short of exactly `k`; a three is one stone short of a straight four,
+/// a four with two ways to complete it, by a move that is not forbidden itself.
+pub fn is_forbidden(engine: &MnkEngine, index: usize) -> bool {
+    let mut scratch = Scratch {
+        engine,
+        board: engine.board().to_vec(),
+    };
+    scratch.is_forbidden(index, 0)
+}
+
+/// A copy of the board to try moves on.
+struct Scratch<'a> {
+    engine: &'a MnkEngine,
+    board: Vec<Option<Player>>,
+}
+
+impl Scratch<'_> {
+    fn is_black(&self, index: usize) -> bool {
+        self.board[index] == Some(Player::X)
+    }
+
+    /// The cells along `dir` through `index` from one edge of the board to the other,
+    /// and where `index` is among them.
+    fn line(&self, index: usize, (dr, dc): (isize, isize)) -> (Vec<usize>, usize) {
+        let width = self.engine.width() as isize;
+        let (row, col) = (index as isize / width, index as isize % width);
+        let cell = |step: isize| {
+            let (r, c) = (row + step * dr, col + step * dc);
+            if r < 0 || c < 0 {
+                return None;
+            }
+            self.engine.index(r as usize, c as usize)
+        };
+
+        let mut line: Vec<usize> = (1..).map_while(|step| cell(-step)).collect();
+        line.reverse();
+        let pos = line.len();
+        line.extend((0..).map_while(cell));
+        (line, pos)
+    }
+
+    /// The length of the black run through `line[pos]`.
+    fn run(&self, line: &[usize], pos: usize) -> usize {
+        let before = line[..pos]
+            .iter()
+            .rev()
+            .take_while(|&&i| self.is_black(i))
+            .count();
+        let after = line[pos..]
+            .iter()
+            .take_while(|&&i| self.is_black(i))
+            .count();
+        before + after
+    }
+
+    /// The fours through `line[pos]`: each window of `k` cells holding `k - 1` black
+    /// stones and one empty cell that would complete exactly `k` in a row, as the
+    /// window's stones and that cell.
+    fn fours(&self, line: &[usize], pos: usize) -> Vec<(Vec<usize>, usize)> {
+        let k = self.engine.k();
+        (pos.saturating_sub(k - 1)..=pos)
+            .filter(|&start| start + k <= line.len())
+            .filter_map(|start| {
+                let window = &line[start..start + k];
+                let stones: Vec<usize> = window
+                    .iter()
+                    .copied()
+                    .filter(|&i| self.is_black(i))
+                    .collect();
+                let empty: Vec<usize> = window
+                    .iter()
+                    .copied()
+                    .filter(|&i| self.board[i].is_none())
+                    .collect();
+                // A black stone just outside the window would make an overline
+                let outside = [start.checked_sub(1), Some(start + k)];
+                let overline = outside
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| line.get(i))
+                    .any(|&i| self.is_black(i));
+                (stones.len() == k - 1 && empty.len() == 1 && !overline).then(|| (stones, empty[0]))
+            })
+            .collect()
+    }
+
+    /// How many fours pass through `line[pos]`. The two ways to complete a straight
+    /// four are the same four.
+    fn four_count(&self, line: &[usize], pos: usize) -> usize {
+        let mut stones: Vec<Vec<usize>> =
+            self.fours(line, pos).into_iter().map(|(s, _)| s).collect();
+        stones.sort();
+        stones.dedup();
+        stones.len()
+    }
+
+    /// Whether a four through `line[pos]` can be completed in two places.
+    fn is_straight_four(&self, line: &[usize], pos: usize) -> bool {
+        let fours = self.fours(line, pos);
+        fours
+            .iter()
+            .any(|(stones, cell)| fours.iter().any(|(s, c)| s == stones && c != cell))
+    }
+
+    /// Whether there is a three through `line[pos]`: a black move nearby that would
+    /// make a straight four and is not forbidden itself.
+    fn is_three(&mut self, line: &[usize], pos: usize, depth: usize) -> bool {
+        let k = self.engine.k();
+        let last = (pos + k - 1).min(line.len() - 1);
+        for &cell in &line[pos.saturating_sub(k - 1)..=last] {
+            if self.board[cell].is_some() {
+                continue;
+            }
+            self.board[cell] = Some(Player::X);
+            let straight = self.is_straight_four(line, pos);
+            self.board[cell] = None;
+            if straight && !self.is_forbidden(cell, depth + 1) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn is_forbidden(&mut self, index: usize, depth: usize) -> bool {
+        if depth > FORBIDDEN_DEPTH {
+            return false;
+        }
+        let k = self.engine.k();
+        self.board[index] = Some(Player::X);
+        let lines = DIRECTIONS.map(|dir| self.line(index, dir));
+        let runs = lines.each_ref().map(|(line, pos)| self.run(line, *pos));
+
+        let forbidden = if runs.contains(&k) {
+            false
+        } else if runs.iter().any(|&run| run > k) {
+            true
+        } else {
+            let fours: Vec<usize> = lines
+                .iter()
+                .map(|(line, pos)| self.four_count(line, *pos))
+                .collect();
+            let mut threes = 0;
+            for ((line, pos), &four) in lines.iter().zip(&fours) {
+                if four == 0 && self.is_three(line, *pos, depth) {
+                    threes += 1;
+                }
+            }
+            fours.iter().sum::<usize>() >= 2 || threes >= 2
+        };
+        self.board[index] = None;
+        forbidden
+    }
+}
+
+/// The two players of a swap2 opening, by who opens rather than by colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    First,
+    Second,
+}
+
+impl Seat {
+    pub fn other(&self) -> Seat {
+        match self {
+            Seat::First => Seat::Second,
+            Seat::Second => Seat::First,
+        }
+    }
+}
+
+/// Where a swap2 opening has got to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swap2Phase {
+    /// The first player places a black, a white and a black stone.
+    Opening,
+    /// The second player takes a colour or chooses to place two more stones.
+    SecondChooses,
+    /// The second player places a white and a black stone.
+    Extending,
+    /// The first player takes a colour.
+    FirstChooses,
+    /// Colours are settled and play goes on as usual.
+    Playing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swap2Choice {
+    /// Play black (X) from here on.
+    Black,
+    /// Play white (O) from here on.
+    White,
+    /// Place two more stones and leave the choice to the first player. Only the
+    /// second player has this option.
+    PlaceTwo,
+}
+
+/// One turn of a swap2 game: a stone, or a choice when one is due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swap2Move {
+    Stone(usize),
+    Choice(Swap2Choice),
+}
+
+/// A game with a swap2 opening. Stones are placed in the usual black, white order
+/// throughout; the opening only decides which player ends up with which colour.
+#[derive(Clone, Debug)]
+pub struct Swap2 {
+    engine: MnkEngine,
+    phase: Swap2Phase,
+    /// Who plays black. The first player does until someone chooses.
+    black: Seat,
+}
+
+impl Swap2 {
+    /// Starts a swap2 opening on `engine`'s board, which is cleared first.
+    pub fn new(mut engine: MnkEngine) -> Self {
+        engine.reset();
+        Self {
+            engine,
+            phase: Swap2Phase::Opening,
+            black: Seat::First,
+        }
+    }
+
+    pub fn engine(&self) -> &MnkEngine {
+        &self.engine
+    }
+
+    /// The game, once the opening is over or abandoned.
+    pub fn into_engine(self) -> MnkEngine {
+        self.engine
+    }
+
+    pub fn phase(&self) -> Swap2Phase {
+        self.phase
+    }
+
+    /// The seat that places the next stone or makes the next choice.
+    pub fn to_act(&self) -> Seat {
+        match self.phase {
+            Swap2Phase::Opening | Swap2Phase::FirstChooses => Seat::First,
+            Swap2Phase::SecondChooses | Swap2Phase::Extending => Seat::Second,
+            Swap2Phase::Playing => self.seat_of(self.engine.current_player),
+        }
+    }
+
+    /// The seat playing `player`'s stones. Only final once the phase is
+    /// [`Swap2Phase::Playing`].
+    pub fn seat_of(&self, player: Player) -> Seat {
+        match player {
+            Player::X => self.black,
+            Player::O => self.black.other(),
+        }
+    }
+
+    /// The colour `seat` plays. Only final once the phase is [`Swap2Phase::Playing`].
+    pub fn player_of(&self, seat: Seat) -> Player {
+        if seat == self.black {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    /// Places the next stone. Fails with [`InvalidGameMoveError::WrongMoveKind`] while
+    /// a colour has to be chosen.
+    pub fn play_move(&mut self, index: usize) -> Result<(), InvalidGameMoveError> {
+        if matches!(
+            self.phase,
+            Swap2Phase::SecondChooses | Swap2Phase::FirstChooses
+        ) {
+            return Err(InvalidGameMoveError::WrongMoveKind);
+        }
+        self.engine.play_move(index)?;
+
+        let stones = self.engine.board().iter().flatten().count();
+        self.phase = match (self.phase, stones) {
+            (Swap2Phase::Opening, 3) => Swap2Phase::SecondChooses,
+            (Swap2Phase::Extending, 5) => Swap2Phase::FirstChooses,
+            (phase, _) => phase,
+        };
+        Ok(())
+    }
+
+    /// Makes the choice the player to act is due. Fails with
+    /// [`InvalidGameMoveError::WrongMoveKind`] if no choice is due or the choice is not
+    /// theirs to make.
+    pub fn choose(&mut self, choice: Swap2Choice) -> Result<(), InvalidGameMoveError> {
+        let chooser = match self.phase {
+            Swap2Phase::SecondChooses => Seat::Second,
+            Swap2Phase::FirstChooses => Seat::First,
+            _ => return Err(InvalidGameMoveError::WrongMoveKind),
+        };
+        self.black = match choice {
+            Swap2Choice::Black => chooser,
+            Swap2Choice::White => chooser.other(),
+            Swap2Choice::PlaceTwo if chooser == Seat::Second => {
+                self.phase = Swap2Phase::Extending;
+                return Ok(());
+            }
+            Swap2Choice::PlaceTwo => return Err(InvalidGameMoveError::WrongMoveKind),
+        };
+        self.phase = Swap2Phase::Playing;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameStatus;
+
+    /// Plays black at `black` and white far away in the bottom row after each.
+    fn renju(black: &[(usize, usize)]) -> MnkEngine {
+        let mut engine = MnkEngine::gomoku().with_rules(GomokuRules::Renju);
+        for (n, &(row, col)) in black.iter().enumerate() {
+            engine.play_move(engine.index(row, col).unwrap()).unwrap();
+            engine.play_move(engine.index(14, 2 * n).unwrap()).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_exactly_five() {
+        let row = 7 * 15;
+        for rules in GomokuRules::ALL {
+            let mut engine = MnkEngine::gomoku().with_rules(rules);
+            // X leaves a gap in a run of six and fills it last
+            for m in [row, 0, row + 1, 2, row + 2, 4, row + 4, 6, row + 5, 8] {
+                engine.play_move(m).unwrap();
+            }
+            let result = engine.play_move(row + 3);
+            match rules {
+                GomokuRules::Freestyle => {
+                    assert!(
+                        matches!(engine.status, GameStatus::Win { ref line, .. } if line.len() == 6)
+                    );
+                }
+                GomokuRules::Standard => {
+                    assert_eq!(result, Ok(()));
+                    assert_eq!(engine.status, GameStatus::Ongoing);
+                }
+                GomokuRules::Renju => {
+                    assert_eq!(result, Err(InvalidGameMoveError::Forbidden));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_renju_white_overline_wins() {
+        let mut engine = MnkEngine::gomoku().with_rules(GomokuRules::Renju);
+        let row = 7 * 15;
+        for m in [0, row, 16, row + 1, 32, row + 2, 3, row + 4, 4, row + 5, 6] {
+            engine.play_move(m).unwrap();
+        }
+        engine.play_move(row + 3).unwrap();
+        assert!(matches!(
+            engine.status,
+            GameStatus::Win { player: Player::O, ref line } if line.len() == 6
+        ));
+    }
+
+    #[test]
+    fn test_double_three_and_double_four() {
+        // Open threes across row 7 and down column 7 meet at (7, 7)
+        let engine = renju(&[(7, 5), (7, 6), (5, 7), (6, 7)]);
+        let center = engine.index(7, 7).unwrap();
+        assert_eq!(
+            engine.validate_move(center),
+            Err(InvalidGameMoveError::Forbidden)
+        );
+        // White is not restricted, and freestyle black is not either
+        let freestyle = engine.clone().with_rules(GomokuRules::Freestyle);
+        assert_eq!(freestyle.validate_move(center), Ok(()));
+
+        // A three blocked at one end cannot become a straight four
+        let mut blocked = renju(&[(7, 5), (7, 6), (5, 7), (6, 7)]);
+        blocked.board_mut()[engine.index(7, 4).unwrap()] = Some(Player::O);
+        assert_eq!(blocked.validate_move(center), Ok(()));
+
+        let engine = renju(&[(7, 4), (7, 5), (7, 6), (4, 7), (5, 7), (6, 7)]);
+        assert!(is_forbidden(&engine, center));
+        // A four and a three together are allowed
+        let engine = renju(&[(7, 4), (7, 5), (7, 6), (5, 7), (6, 7)]);
+        assert!(!is_forbidden(&engine, center));
+    }
+
+    #[test]
+    fn test_swap2() {
+        let engine = MnkEngine::gomoku();
+        let mut game = Swap2::new(engine);
+        for m in [112, 113, 114] {
+            assert_eq!(game.to_act(), Seat::First);
+            game.play_move(m).unwrap();
+        }
+        assert_eq!(game.phase(), Swap2Phase::SecondChooses);
+        assert_eq!(game.play_move(0), Err(InvalidGameMoveError::WrongMoveKind));
+
+        // The second player places two more and the first player takes white
+        game.choose(Swap2Choice::PlaceTwo).unwrap();
+        game.play_move(127).unwrap();
+        assert_eq!(game.to_act(), Seat::Second);
+        game.play_move(128).unwrap();
+        assert_eq!(
+            game.choose(Swap2Choice::PlaceTwo),
+            Err(InvalidGameMoveError::WrongMoveKind)
+        );
+        game.choose(Swap2Choice::White).unwrap();
+        assert_eq!(game.phase(), Swap2Phase::Playing);
+        assert_eq!(game.seat_of(Player::O), Seat::First);
+        // White is to move, so the first player is
+        assert_eq!(game.to_act(), Seat::First);
+        assert_eq!(game.engine().current_player, Player::O);
+    }
+}
@@ -1,3 +1,25 @@
+pub mod agent;
+pub mod ai;
+pub mod analysis;
+pub mod game;
+pub mod gomoku;
+pub mod mcts;
+pub mod mnk;
+pub mod notakto;
+pub mod notation;
+pub mod quantum;
+pub mod qubic;
+pub mod record;
+pub mod rng;
+pub mod rules;
+pub mod symmetry;
+pub mod tablebase;
+pub mod ultimate;
+pub mod wild;
+pub mod zobrist;
+
+use rules::{RuleSet, Rules};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub enum Player {
@@ -14,11 +36,16 @@ impl Player {
     }
 }
 
+/// Outcome of a game. `L` is the winning line: the classic engine always
+/// reports three board indices, while [`mnk::MnkEngine`] reports a `Vec`.
+///
+/// Under [`rules::RuleSet::Misere`] the line is the one the loser completed, and
+/// `player` is still the winner.
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 #[cfg_attr(feature = "wasm", serde(tag = "type", content = "value"))]
-pub enum GameStatus {
-    Win { player: Player, line: [usize; 3] },
+pub enum GameStatus<L = [usize; 3]> {
+    Win { player: Player, line: L },
     Draw,
     Ongoing,
 }
@@ -30,13 +57,29 @@ pub enum InvalidGameMoveError {
     GameAlreadyWon,
     /// Attempted to play a move on an already occupied space.
     SpaceOccupied,
+    /// Attempted to play a move outside the board.
+    OutOfBounds,
+    /// Attempted to play on a small board other than the one the opponent sent you to,
+    /// or on one that is out of play.
+    WrongBoard,
+    /// Attempted to place a piece above an empty cell where pieces fall to the bottom.
+    Floating,
+    /// Attempted a kind of move the game does not allow right now, such as placing a
+    /// quantum mark while an entanglement cycle waits to be collapsed.
+    WrongMoveKind,
+    /// Attempted to collapse an entanglement cycle into a square outside the cycle.
+    NotInCycle,
+    /// Attempted a move the rules forbid for this player, such as a double three for
+    /// black under Renju rules.
+    Forbidden,
 }
 
 /// A valid board position for tic-tac-toe, guaranteed to be in the range 0-8.
 ///
 /// This type makes it impossible to construct an invalid position,
 /// ensuring that only valid board indices can be used.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub struct Position(u8);
 
 impl Position {
@@ -57,91 +100,303 @@ impl Position {
 #[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 #[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
 pub struct GameEngine {
+    /// The cells in row-major order. Direct edits are picked up by the next move,
+    /// undo or redo, which then rebuild the bitboards and hash and clear the history.
     pub board: [Option<Player>; 9],
     pub current_player: Player,
     pub status: GameStatus,
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    log: MoveLog,
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    hash: u64,
+    /// Cells held by X and O as bitmasks (bit `i` is cell `i`), mirroring `board`.
+    #[cfg_attr(feature = "wasm", serde(skip))]
+    bits: [u16; 2],
+    /// Only serialized for non-standard rules, so standard games look as they always did.
+    #[cfg_attr(feature = "wasm", serde(skip_serializing_if = "RuleSet::is_standard"))]
+    rules: RuleSet,
+}
+
+/// Every cell of the board.
+pub(crate) const FULL_BOARD: u16 = 0b111_111_111;
+
+/// The eight winning lines as bitmasks, with their cells.
+pub(crate) const WINNING_LINES: [(u16, [usize; 3]); 8] = [
+    // horizontal
+    (0b000_000_111, [0, 1, 2]),
+    (0b000_111_000, [3, 4, 5]),
+    (0b111_000_000, [6, 7, 8]),
+    // vertical
+    (0b001_001_001, [0, 3, 6]),
+    (0b010_010_010, [1, 4, 7]),
+    (0b100_100_100, [2, 5, 8]),
+    // diagonal
+    (0b100_010_001, [0, 4, 8]),
+    (0b001_010_100, [2, 4, 6]),
+];
+
+fn bit_index(player: Player) -> usize {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+    }
+}
+
+pub(crate) fn bits_from_board(board: &[Option<Player>; 9]) -> [u16; 2] {
+    let mut bits = [0; 2];
+    for (i, cell) in board.iter().enumerate() {
+        if let Some(player) = cell {
+            bits[bit_index(*player)] |= 1 << i;
+        }
+    }
+    bits
+}
+
+pub(crate) fn status_from_bits([x, o]: [u16; 2]) -> GameStatus {
+    for (mask, line) in WINNING_LINES {
+        for (player, bits) in [(Player::X, x), (Player::O, o)] {
+            if bits & mask == mask {
+                return GameStatus::Win { player, line };
+            }
+        }
+    }
+    if x | o == FULL_BOARD {
+        return GameStatus::Draw;
+    }
+    GameStatus::Ongoing
+}
+
+/// Whether the cells in `bits` include a whole winning line.
+pub(crate) fn has_line(bits: u16) -> bool {
+    WINNING_LINES.iter().any(|&(mask, _)| bits & mask == mask)
+}
+
+/// The set cells of `mask`, lowest first.
+pub(crate) fn cells(mut mask: u16) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        let cell = (mask != 0).then(|| mask.trailing_zeros() as u8);
+        mask &= mask.wrapping_sub(1);
+        cell
+    })
+}
+
+/// Status right after `player` moved in an ongoing game. Only the mover can have
+/// completed a line, so only their lines are checked.
+fn status_after_move(bits: [u16; 2], player: Player) -> GameStatus {
+    let own = bits[bit_index(player)];
+    if own.count_ones() >= 3 {
+        for (mask, line) in WINNING_LINES {
+            if own & mask == mask {
+                return GameStatus::Win { player, line };
+            }
+        }
+    }
+    if bits[0] | bits[1] == FULL_BOARD {
+        return GameStatus::Draw;
+    }
+    GameStatus::Ongoing
+}
+
+/// Moves in the order they were played. A game has at most nine moves, so the log
+/// is a fixed array and `GameEngine` stays `Copy`.
+///
+/// `moves[..len]` are on the board; `moves[len..end]` were undone and can be redone.
+#[derive(Copy, Clone, Default)]
+struct MoveLog {
+    moves: [u8; 9],
+    len: u8,
+    end: u8,
+}
+
+impl Default for GameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameEngine {
     pub fn new() -> Self {
+        Self::with_rules(RuleSet::Standard)
+    }
+
+    /// Creates an empty board played under `rules`.
+    pub fn with_rules(rules: RuleSet) -> Self {
         Self {
             board: [None; 9],
             current_player: Player::X,
             status: GameStatus::Ongoing,
+            log: MoveLog::default(),
+            hash: 0,
+            bits: [0; 2],
+            rules,
         }
     }
 
+    /// Starts a new game under the same rules.
     pub fn reset(&mut self) {
-        *self = Self::new();
+        *self = Self::with_rules(self.rules);
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
     }
 
     pub fn play_move(&mut self, pos: Position) -> Result<(), InvalidGameMoveError> {
+        self.sync_if_edited();
         self.validate_move(pos)?;
 
+        // A new move discards any undone moves
+        self.log.end = self.log.len;
+        self.apply_move(pos);
+        Ok(())
+    }
+
+    fn apply_move(&mut self, pos: Position) {
         // Apply Move
         self.board[pos.to_index()] = Some(self.current_player);
+        self.hash ^= zobrist::key(self.current_player, pos);
+        self.bits[bit_index(self.current_player)] |= 1 << pos.0;
+
+        // Record Move
+        self.log.moves[self.log.len as usize] = pos.0;
+        self.log.len += 1;
+        self.log.end = self.log.end.max(self.log.len);
 
-        // Update Game Status
-        self.status = self.calculate_status();
+        // Update Game Status. The same as `Rules::status`, but only the mover's
+        // lines need checking.
+        self.status = self
+            .rules
+            .adjudicate(status_after_move(self.bits, self.current_player));
 
         // Switch Player
         self.current_player = self.current_player.next();
-        Ok(())
     }
 
-    pub fn validate_move(&self, pos: Position) -> Result<(), InvalidGameMoveError> {
-        if self.status != GameStatus::Ongoing {
-            return Err(InvalidGameMoveError::GameAlreadyWon);
+    /// Takes back the last move, returning its position, or `None` if no moves were played.
+    pub fn undo(&mut self) -> Option<Position> {
+        self.sync_if_edited();
+        if self.log.len == 0 {
+            return None;
         }
-        let pos: usize = pos.to_index();
-        if self.board[pos].is_some() {
-            return Err(InvalidGameMoveError::SpaceOccupied);
+        self.log.len -= 1;
+        let pos = Position(self.log.moves[self.log.len as usize]);
+
+        // The player who made the move is to move again
+        self.current_player = self.board[pos.to_index()].take()?;
+        self.hash ^= zobrist::key(self.current_player, pos);
+        self.bits[bit_index(self.current_player)] &= !(1 << pos.0);
+        self.status = self.rules.adjudicate(status_from_bits(self.bits));
+        Some(pos)
+    }
+
+    /// Replays the last undone move, returning its position, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Position> {
+        self.sync_if_edited();
+        if self.log.len == self.log.end {
+            return None;
         }
-        Ok(())
+        let pos = Position(self.log.moves[self.log.len as usize]);
+        self.apply_move(pos);
+        Some(pos)
     }
 
-    pub fn calculate_status(&self) -> GameStatus {
-        const WINNING_COMBINATIONS: [[usize; 3]; 8] = [
-            // horizontal
-            [0, 1, 2],
-            [3, 4, 5],
-            [6, 7, 8],
-            // vertical
-            [0, 3, 6],
-            [1, 4, 7],
-            [2, 5, 8],
-            // diagonal
-            [0, 4, 8],
-            [2, 4, 6],
-        ];
-
-        for [a, b, c] in WINNING_COMBINATIONS.iter() {
-            if let (Some(p1), Some(p2), Some(p3)) = (self.board[*a], self.board[*b], self.board[*c])
-            {
-                if p1 == p2 && p2 == p3 {
-                    {
-                        return GameStatus::Win {
-                            player: p1,
-                            line: [*a, *b, *c],
-                        };
-                    }
-                }
-            }
+    pub fn can_undo(&self) -> bool {
+        self.log.len > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.log.len < self.log.end
+    }
+
+    /// The moves currently on the board, in the order they were played.
+    pub fn history(&self) -> Vec<Position> {
+        self.log.moves[..self.log.len as usize]
+            .iter()
+            .map(|&pos| Position(pos))
+            .collect()
+    }
+
+    /// Zobrist hash of the board, kept up to date as moves are played and undone.
+    /// See [`zobrist`] for how it is built.
+    pub fn zobrist_hash(&self) -> u64 {
+        if self.is_edited() {
+            return zobrist::hash_board(&self.board);
         }
+        self.hash
+    }
+
+    /// Cells held by `player` as a bitmask (bit `i` is cell `i`).
+    pub(crate) fn cells_of(&self, player: Player) -> u16 {
+        self.current_bits()[bit_index(player)]
+    }
 
-        // Check draw (if no winner)
-        if self.board.iter().all(|&p| p.is_some()) {
-            return GameStatus::Draw;
+    /// Empty cells as a bitmask (bit `i` is cell `i`).
+    pub(crate) fn empty_cells(&self) -> u16 {
+        let [x, o] = self.current_bits();
+        !(x | o) & FULL_BOARD
+    }
+
+    /// The bitboards for `board` as it is now, which is `bits` unless the cells were
+    /// edited directly since the last move.
+    fn current_bits(&self) -> [u16; 2] {
+        bits_from_board(&self.board)
+    }
+
+    /// Whether `board` was edited directly, so `bits` and `hash` no longer mirror it.
+    fn is_edited(&self) -> bool {
+        self.current_bits() != self.bits
+    }
+
+    /// Catches up with direct edits of `board`. The move history is cleared, since it
+    /// no longer leads to the board.
+    fn sync_if_edited(&mut self) {
+        if self.is_edited() {
+            self.log = MoveLog::default();
+            self.sync_from_board();
         }
+    }
 
-        return GameStatus::Ongoing;
+    /// Rebuilds the bitboards, hash and status after `board` was filled in directly.
+    pub(crate) fn sync_from_board(&mut self) {
+        self.bits = bits_from_board(&self.board);
+        self.hash = zobrist::hash_board(&self.board);
+        self.status = self.rules.adjudicate(status_from_bits(self.bits));
+    }
+
+    pub fn validate_move(&self, pos: Position) -> Result<(), InvalidGameMoveError> {
+        if self.status != GameStatus::Ongoing {
+            return Err(InvalidGameMoveError::GameAlreadyWon);
+        }
+        self.rules
+            .validate_move(&self.board, self.current_player, pos)
+    }
+
+    /// The positions the current player may play, in board order. Yields nothing once
+    /// the game is over.
+    pub fn legal_moves(&self) -> impl Iterator<Item = Position> + use<> {
+        let empty = match self.status {
+            GameStatus::Ongoing => self.empty_cells(),
+            _ => 0,
+        };
+        cells(empty).map(Position)
+    }
+
+    /// The game after playing `pos`, leaving this one untouched.
+    pub fn with_move(&self, pos: Position) -> Result<GameEngine, InvalidGameMoveError> {
+        let mut next = *self;
+        next.play_move(pos)?;
+        Ok(next)
+    }
+
+    /// Computes the status from `board`, so it is correct even after direct edits.
+    pub fn calculate_status(&self) -> GameStatus {
+        self.rules.status(&self.board)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
 
     #[test]
     fn test_position_new() {
@@ -151,6 +406,91 @@ mod tests {
         assert!(Position::new(255).is_none());
     }
 
+    #[test]
+    fn test_bitboards_follow_board() {
+        let mut engine = GameEngine::new();
+        for &m in &[4, 0, 8, 2, 1, 7] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+            assert_eq!(engine.bits, bits_from_board(&engine.board));
+            assert_eq!(engine.status, engine.calculate_status());
+        }
+        while engine.undo().is_some() {
+            assert_eq!(engine.bits, bits_from_board(&engine.board));
+            assert_eq!(engine.status, engine.calculate_status());
+        }
+        assert_eq!(engine.empty_cells(), FULL_BOARD);
+    }
+
+    #[test]
+    fn test_direct_board_edits_are_picked_up() {
+        let mut engine = GameEngine::new();
+        engine.play_move(Position(4)).unwrap();
+        engine.board = [None; 9];
+        engine.board[0] = Some(Player::X);
+        assert_eq!(engine.zobrist_hash(), zobrist::hash_board(&engine.board));
+        assert_eq!(engine.legal_moves().count(), 8);
+
+        // The move history no longer leads here, so only the new move can be undone
+        engine.play_move(Position(4)).unwrap();
+        assert_eq!(engine.bits, bits_from_board(&engine.board));
+        assert_eq!(engine.history(), [Position(4)]);
+        assert_eq!(engine.undo(), Some(Position(4)));
+        assert_eq!(engine.undo(), None);
+        assert_eq!(engine.board[0], Some(Player::X));
+    }
+
+    #[test]
+    fn test_misere_line_loses() {
+        let mut engine = GameEngine::with_rules(RuleSet::Misere);
+        for &m in &[0, 3, 1, 4, 2] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        // X completed the top row, so O wins
+        let lost = GameStatus::Win {
+            player: Player::O,
+            line: [0, 1, 2],
+        };
+        assert_eq!(engine.status, lost);
+        assert_eq!(engine.calculate_status(), lost);
+        engine.undo();
+        engine.redo();
+        assert_eq!(engine.status, lost);
+
+        engine.reset();
+        assert_eq!(engine.rules(), RuleSet::Misere);
+        assert_eq!(engine.status, GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn test_legal_moves_and_with_move() {
+        let engine = GameEngine::new();
+        assert_eq!(engine.legal_moves().count(), 9);
+
+        let next = engine.with_move(Position(4)).unwrap();
+        assert_eq!(engine.board[4], None);
+        assert_eq!(next.board[4], Some(Player::X));
+        assert_eq!(next.current_player, Player::O);
+        assert_eq!(
+            next.legal_moves().map(|p| p.0).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 5, 6, 7, 8]
+        );
+        assert_eq!(
+            next.with_move(Position(4)).err(),
+            Some(InvalidGameMoveError::SpaceOccupied)
+        );
+
+        // X completes the top row: no moves remain
+        let mut won = GameEngine::new();
+        for m in [0, 3, 1, 4, 2] {
+            won = won.with_move(Position(m)).unwrap();
+        }
+        assert_eq!(won.legal_moves().count(), 0);
+        assert_eq!(
+            won.with_move(Position(8)).err(),
+            Some(InvalidGameMoveError::GameAlreadyWon)
+        );
+    }
+
     #[test]
     fn test_update_next_player() {
         assert_eq!(Player::O.next(), Player::X);
@@ -222,6 +562,57 @@ mod tests {
         assert_eq!(engine.status, GameStatus::Ongoing);
     }
 
+    #[test]
+    fn test_undo_redo() {
+        let mut engine = GameEngine::new();
+        for &m in &[0, 3, 1, 4, 2] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        assert!(matches!(engine.status, GameStatus::Win { .. }));
+
+        assert_eq!(engine.undo(), Position::new(2));
+        assert_eq!(engine.board[2], None);
+        assert_eq!(engine.current_player, Player::X);
+        assert_eq!(engine.status, GameStatus::Ongoing);
+
+        assert_eq!(engine.undo(), Position::new(4));
+        assert_eq!(engine.current_player, Player::O);
+        assert!(engine.can_redo());
+
+        assert_eq!(engine.redo(), Position::new(4));
+        assert_eq!(engine.redo(), Position::new(2));
+        assert_eq!(engine.redo(), None);
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 1, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_move_clears_redo() {
+        let mut engine = GameEngine::new();
+        for &m in &[0, 3, 1] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        engine.undo();
+        engine.undo();
+        engine.play_move(Position::new(8).unwrap()).unwrap();
+        assert!(!engine.can_redo());
+        let history: Vec<usize> = engine.history().iter().map(|p| p.to_index()).collect();
+        assert_eq!(history, vec![0, 8]);
+    }
+
+    #[test]
+    fn test_undo_empty() {
+        let mut engine = GameEngine::new();
+        assert!(!engine.can_undo());
+        assert_eq!(engine.undo(), None);
+        assert_eq!(engine.redo(), None);
+    }
+
     #[test]
     fn test_serialize_game_status() {
         let mut engine = GameEngine::new();
@@ -229,7 +620,7 @@ mod tests {
             player: Player::O,
             line: [0, 1, 2],
         };
-        let win = serde_json::to_value(&engine).unwrap();
+        let win = serde_json::to_value(engine).unwrap();
         let expected = serde_json::json!({
             "board": [null, null, null, null, null, null, null, null, null],
             "currentPlayer": "X",
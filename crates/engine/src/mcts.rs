@@ -0,0 +1,500 @@
+//! Monte Carlo Tree Search with UCT selection.
+//!
+//! Minimax needs the whole game tree, which is fine for the classic board but hopeless
+//! for larger m,n,k boards or ultimate tic-tac-toe. MCTS instead grows a tree by
+//! repeatedly selecting a promising line, adding one new position, finishing the game
+//! with random moves and crediting the result back up the line. It only needs the
+//! rules, so the same search plays every variant that implements [`SearchGame`].
+
+use crate::agent::Agent;
+use crate::game::Game;
+use crate::gomoku::{Seat, Swap2, Swap2Choice, Swap2Move, Swap2Phase};
+use crate::mnk::MnkEngine;
+use crate::quantum::{QuantumEngine, QuantumMove, QuantumStatus};
+use crate::rng::Rng;
+use crate::rules::Rules;
+use crate::ultimate::{UltimateEngine, UltimatePosition};
+use crate::{GameEngine, GameStatus, Player, Position};
+use std::time::{Duration, Instant};
+
+/// The rules of a game as the search sees them.
+pub trait SearchGame: Clone {
+    type Move: Copy;
+
+    /// The player whose turn it is.
+    fn to_move(&self) -> Player;
+
+    /// Every legal move, or none once the game is over.
+    fn legal_moves(&self) -> Vec<Self::Move>;
+
+    /// Plays a move taken from [`SearchGame::legal_moves`].
+    fn play(&mut self, mv: Self::Move);
+
+    /// `None` while the game is ongoing, otherwise the winner (`None` for a draw).
+    fn outcome(&self) -> Option<Option<Player>>;
+}
+
+fn outcome_of<L>(status: &GameStatus<L>) -> Option<Option<Player>> {
+    match status {
+        GameStatus::Win { player, .. } => Some(Some(*player)),
+        GameStatus::Draw => Some(None),
+        GameStatus::Ongoing => None,
+    }
+}
+
+impl SearchGame for GameEngine {
+    type Move = Position;
+
+    fn to_move(&self) -> Player {
+        self.current_player
+    }
+
+    fn legal_moves(&self) -> Vec<Position> {
+        GameEngine::legal_moves(self).collect()
+    }
+
+    fn play(&mut self, pos: Position) {
+        let _ = self.play_move(pos);
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        outcome_of(&self.status)
+    }
+}
+
+impl<R: Rules + Clone> SearchGame for Game<R> {
+    type Move = R::Move;
+
+    fn to_move(&self) -> Player {
+        self.current_player()
+    }
+
+    fn legal_moves(&self) -> Vec<R::Move> {
+        Game::legal_moves(self)
+    }
+
+    fn play(&mut self, mv: R::Move) {
+        let _ = self.play_move(mv);
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        outcome_of(self.status())
+    }
+}
+
+impl SearchGame for MnkEngine {
+    type Move = usize;
+
+    fn to_move(&self) -> Player {
+        self.current_player
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        MnkEngine::legal_moves(self)
+    }
+
+    fn play(&mut self, index: usize) {
+        let _ = self.play_move(index);
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        outcome_of(&self.status)
+    }
+}
+
+impl SearchGame for UltimateEngine {
+    type Move = UltimatePosition;
+
+    fn to_move(&self) -> Player {
+        self.current_player
+    }
+
+    fn legal_moves(&self) -> Vec<UltimatePosition> {
+        (0..9)
+            .flat_map(|board| (0..9).map(move |cell| (board, cell)))
+            .map(|(board, cell)| UltimatePosition {
+                board: Position(board),
+                cell: Position(cell),
+            })
+            .filter(|&pos| self.validate_move(pos).is_ok())
+            .collect()
+    }
+
+    fn play(&mut self, pos: UltimatePosition) {
+        let _ = self.play_move(pos);
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        outcome_of(&self.status)
+    }
+}
+
+/// A collapse keeps the turn with the collapsing player, who then places a mark; the
+/// search follows whoever is to move. A runner-up line's half point is not counted.
+impl SearchGame for QuantumEngine {
+    type Move = QuantumMove;
+
+    fn to_move(&self) -> Player {
+        self.current_player
+    }
+
+    fn legal_moves(&self) -> Vec<QuantumMove> {
+        QuantumEngine::legal_moves(self)
+    }
+
+    fn play(&mut self, mv: QuantumMove) {
+        let _ = self.play_move(mv);
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        match self.status {
+            QuantumStatus::Win { player, .. } => Some(Some(player)),
+            QuantumStatus::Draw => Some(None),
+            QuantumStatus::Ongoing => None,
+        }
+    }
+}
+
+/// Colours are only settled partway through the opening, so the search plays the
+/// seats instead: X is the first player and O the second, whatever they play.
+impl SearchGame for Swap2 {
+    type Move = Swap2Move;
+
+    fn to_move(&self) -> Player {
+        seat_player(self.to_act())
+    }
+
+    fn legal_moves(&self) -> Vec<Swap2Move> {
+        if self.outcome().is_some() {
+            return Vec::new();
+        }
+        let choices = match self.phase() {
+            Swap2Phase::SecondChooses => vec![
+                Swap2Choice::Black,
+                Swap2Choice::White,
+                Swap2Choice::PlaceTwo,
+            ],
+            Swap2Phase::FirstChooses => vec![Swap2Choice::Black, Swap2Choice::White],
+            _ => {
+                let stones = self.engine().legal_moves().into_iter();
+                return stones.map(Swap2Move::Stone).collect();
+            }
+        };
+        choices.into_iter().map(Swap2Move::Choice).collect()
+    }
+
+    fn play(&mut self, mv: Swap2Move) {
+        let _ = match mv {
+            Swap2Move::Stone(index) => self.play_move(index),
+            Swap2Move::Choice(choice) => self.choose(choice),
+        };
+    }
+
+    fn outcome(&self) -> Option<Option<Player>> {
+        let winner = outcome_of(&self.engine().status)?;
+        Some(winner.map(|player| seat_player(self.seat_of(player))))
+    }
+}
+
+fn seat_player(seat: Seat) -> Player {
+    match seat {
+        Seat::First => Player::X,
+        Seat::Second => Player::O,
+    }
+}
+
+/// How long a search may run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    /// A fixed number of playouts, which makes results reproducible from the seed.
+    Iterations(u32),
+    /// Wall-clock time. Not available on `wasm32-unknown-unknown`, which has no clock;
+    /// use an iteration budget there.
+    Time(Duration),
+}
+
+/// The usual UCT exploration constant, sqrt(2).
+pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+struct Node<M> {
+    /// The move that led here; `None` for the root.
+    mv: Option<M>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Legal moves that have no child node yet.
+    untried: Vec<M>,
+    /// The player who made `mv`; results are credited from their point of view.
+    mover: Player,
+    visits: u32,
+    /// One point per win for `mover`, half a point per draw.
+    reward: f64,
+}
+
+/// A Monte Carlo Tree Search player for any [`SearchGame`].
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    pub budget: Budget,
+    /// Weight of the UCT exploration term; higher values try more unvisited lines.
+    pub exploration: f64,
+    rng: Rng,
+}
+
+impl Mcts {
+    pub fn new(budget: Budget, seed: u64) -> Self {
+        Self {
+            budget,
+            exploration: DEFAULT_EXPLORATION,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Searches `game` within the budget and returns the most visited move, or `None`
+    /// if the game is over.
+    pub fn search<G: SearchGame>(&mut self, game: &G) -> Option<G::Move> {
+        self.search_with_clock(game, Instant::now)
+    }
+
+    /// [`Mcts::search`] reading the time from `now`. The clock is only read under a
+    /// time budget, so iteration budgets also work where there is no clock.
+    fn search_with_clock<G: SearchGame>(
+        &mut self,
+        game: &G,
+        now: impl Fn() -> Instant,
+    ) -> Option<G::Move> {
+        if game.outcome().is_some() {
+            return None;
+        }
+        let mut tree = vec![Node {
+            mv: None,
+            parent: None,
+            children: Vec::new(),
+            untried: game.legal_moves(),
+            mover: game.to_move().next(),
+            visits: 0,
+            reward: 0.0,
+        }];
+
+        let deadline = match self.budget {
+            Budget::Time(limit) => Some(now() + limit),
+            Budget::Iterations(_) => None,
+        };
+        let mut iterations = 0;
+        loop {
+            let done = match (self.budget, deadline) {
+                (Budget::Iterations(limit), _) => iterations >= limit,
+                (Budget::Time(_), deadline) => {
+                    iterations > 0 && deadline.is_none_or(|deadline| now() >= deadline)
+                }
+            };
+            if done {
+                break;
+            }
+            self.iterate(&mut tree, game);
+            iterations += 1;
+        }
+
+        tree[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)
+            .and_then(|&child| tree[child].mv)
+    }
+
+    /// One round of selection, expansion, simulation and backpropagation.
+    fn iterate<G: SearchGame>(&mut self, tree: &mut Vec<Node<G::Move>>, root: &G) {
+        let mut game = root.clone();
+        let mut node = 0;
+
+        // Selection: descend through fully expanded nodes by UCT score
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            node = self.select_child(tree, node);
+            game.play(tree[node].mv.expect("only the root has no move"));
+        }
+
+        // Expansion: add one untried move as a new child
+        if !tree[node].untried.is_empty() {
+            let untried = &mut tree[node].untried;
+            let mv = untried.swap_remove(self.rng.gen_range(untried.len()));
+            let mover = game.to_move();
+            game.play(mv);
+            let child = tree.len();
+            tree.push(Node {
+                mv: Some(mv),
+                parent: Some(node),
+                children: Vec::new(),
+                untried: game.legal_moves(),
+                mover,
+                visits: 0,
+                reward: 0.0,
+            });
+            tree[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation: finish the game at random
+        let winner = loop {
+            if let Some(winner) = game.outcome() {
+                break winner;
+            }
+            let moves = game.legal_moves();
+            let Some(mv) = self.rng.choose(&moves) else {
+                break None;
+            };
+            game.play(mv);
+        };
+
+        // Backpropagation
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.reward += match winner {
+                Some(player) if player == node.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = node.parent;
+        }
+    }
+
+    fn select_child<M>(&self, tree: &[Node<M>], parent: usize) -> usize {
+        let log_visits = (tree[parent].visits as f64).ln();
+        let uct = |child: usize| {
+            let node = &tree[child];
+            let visits = node.visits as f64;
+            node.reward / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+        *tree[parent]
+            .children
+            .iter()
+            .max_by(|&&a, &&b| uct(a).total_cmp(&uct(b)))
+            .expect("selection only visits expanded nodes")
+    }
+}
+
+impl Agent for Mcts {
+    fn choose_move(&mut self, engine: &GameEngine) -> Option<Position> {
+        self.search(engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::play_game;
+    use crate::ai::{AiPlayer, Difficulty};
+    use crate::rules::RuleSet;
+
+    fn classic(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position(m)).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_takes_and_blocks_wins() {
+        let mut mcts = Mcts::new(Budget::Iterations(2000), 1);
+        // X to play completes the top row
+        assert_eq!(mcts.search(&classic(&[0, 3, 1, 4])), Some(Position(2)));
+        // O must block the top row
+        assert_eq!(mcts.search(&classic(&[0, 4, 1])), Some(Position(2)));
+        assert_eq!(mcts.search(&classic(&[0, 3, 1, 4, 2])), None);
+
+        // The same position through the generic rules engine
+        let mut game = Game::new(RuleSet::Standard);
+        for m in [0, 3, 1, 4] {
+            game.play_move(Position(m)).unwrap();
+        }
+        assert_eq!(mcts.search(&game), Some(Position(2)));
+    }
+
+    #[test]
+    fn test_same_seed_same_moves() {
+        let engine = classic(&[4]);
+        let first = Mcts::new(Budget::Iterations(300), 9).search(&engine);
+        for _ in 0..3 {
+            assert_eq!(Mcts::new(Budget::Iterations(300), 9).search(&engine), first);
+        }
+    }
+
+    #[test]
+    fn test_holds_perfect_play_to_a_draw() {
+        let mut perfect = AiPlayer::new(Difficulty::Perfect, 0);
+        let mut mcts = Mcts::new(Budget::Iterations(3000), 3);
+        assert_eq!(play_game(&mut mcts, &mut perfect).status, GameStatus::Draw);
+        assert_eq!(play_game(&mut perfect, &mut mcts).status, GameStatus::Draw);
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let mut mcts = Mcts::new(Budget::Time(Duration::from_millis(20)), 0);
+        assert!(mcts.search(&GameEngine::new()).is_some());
+    }
+
+    #[test]
+    fn test_iteration_budget_never_reads_the_clock() {
+        let mut mcts = Mcts::new(Budget::Iterations(200), 0);
+        let no_clock = || -> Instant { panic!("read the clock under an iteration budget") };
+        assert!(
+            mcts.search_with_clock(&GameEngine::new(), no_clock)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        // 5x5, four in a row: X has 0, 1, 2 and must finish at 3 before O does at 8
+        let mut mnk = MnkEngine::new(5, 5, 4).unwrap();
+        for m in [0, 5, 1, 6, 2, 7] {
+            mnk.play_move(m).unwrap();
+        }
+        let mut mcts = Mcts::new(Budget::Iterations(2000), 5);
+        assert_eq!(mcts.search(&mnk), Some(3));
+
+        // Ultimate: any answer must be on the board X sent O to
+        let mut ultimate = UltimateEngine::new();
+        ultimate
+            .play_move(UltimatePosition::new(4, 2).unwrap())
+            .unwrap();
+        let mv = mcts.search(&ultimate).unwrap();
+        assert_eq!(mv.board, Position(2));
+        assert!(ultimate.validate_move(mv).is_ok());
+    }
+
+    #[test]
+    fn test_quantum_collapse() {
+        // O entangles the same two squares as X, closing a cycle X must collapse
+        let mut quantum = QuantumEngine::new();
+        for mv in [
+            QuantumMove::Spooky(Position(0), Position(1)),
+            QuantumMove::Spooky(Position(0), Position(1)),
+        ] {
+            quantum.play_move(mv).unwrap();
+        }
+        let mut mcts = Mcts::new(Budget::Iterations(500), 2);
+        let mv = mcts.search(&quantum).unwrap();
+        assert!(matches!(mv, QuantumMove::Collapse(_)), "{:?}", mv);
+        assert!(quantum.validate_move(mv).is_ok());
+    }
+
+    #[test]
+    fn test_swap2_seats() {
+        let mut swap2 = Swap2::new(MnkEngine::new(5, 5, 4).unwrap());
+        let mut mcts = Mcts::new(Budget::Iterations(2000), 4);
+        assert!(matches!(mcts.search(&swap2), Some(Swap2Move::Stone(_))));
+        for m in [0, 5, 1] {
+            swap2.play_move(m).unwrap();
+        }
+        assert!(matches!(mcts.search(&swap2), Some(Swap2Move::Choice(_))));
+
+        // The second player takes black and threatens 3; the first player, now white,
+        // has to block it
+        swap2.choose(Swap2Choice::Black).unwrap();
+        swap2.play_move(6).unwrap();
+        swap2.play_move(2).unwrap();
+        assert_eq!(swap2.to_act(), Seat::First);
+        assert_eq!(mcts.search(&swap2), Some(Swap2Move::Stone(3)));
+    }
+}
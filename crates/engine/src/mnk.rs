@@ -0,0 +1,369 @@
+//! Generalized m,n,k game: any width x height board, first to get k in a row wins.
+//!
+//! Classic tic-tac-toe is the 3,3,3 game and Gomoku is 15,15,5. Instead of a table of
+//! winning lines, wins are detected by scanning outward from the last move.
+//!
+//! With gravity, pieces fall to the lowest empty cell of their column, so a move is
+//! just a choice of column. Connect Four is the 7,6,4 game with gravity.
+//!
+//! A [`GomokuRules`] setting decides whether runs longer than k win and which moves X
+//! may not play; see [`crate::gomoku`].
+
+use crate::gomoku::{self, GomokuRules};
+use crate::{GameStatus, InvalidGameMoveError, Player};
+
+/// Row/column steps for the four line directions: horizontal, vertical and both diagonals.
+pub(crate) const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub struct MnkEngine {
+    width: usize,
+    height: usize,
+    k: usize,
+    /// Whether pieces fall to the bottom of their column.
+    gravity: bool,
+    /// Which runs win and which moves are forbidden. Freestyle unless set.
+    rules: GomokuRules,
+    /// Cells in row-major order; index `row * width + col`. Row 0 is the top.
+    board: Vec<Option<Player>>,
+    pub current_player: Player,
+    pub status: GameStatus<Vec<usize>>,
+}
+
+impl MnkEngine {
+    /// Creates an empty `width` x `height` board where `k` in a row wins.
+    ///
+    /// Returns `None` if the board is empty or `k` cannot fit in any direction.
+    pub fn new(width: usize, height: usize, k: usize) -> Option<Self> {
+        if width == 0 || height == 0 || k == 0 || k > width.max(height) {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            k,
+            gravity: false,
+            rules: GomokuRules::Freestyle,
+            board: vec![None; width * height],
+            current_player: Player::X,
+            status: GameStatus::Ongoing,
+        })
+    }
+
+    /// The 15x15 five-in-a-row board used for Gomoku.
+    pub fn gomoku() -> Self {
+        Self::new(15, 15, 5).expect("15,15,5 is a valid board")
+    }
+
+    /// The 7 wide, 6 tall Connect Four board, with gravity and four in a row.
+    pub fn connect_four() -> Self {
+        Self::new(7, 6, 4)
+            .expect("7,6,4 is a valid board")
+            .with_gravity()
+    }
+
+    /// The same board with pieces falling to the bottom of their column.
+    pub fn with_gravity(mut self) -> Self {
+        self.gravity = true;
+        self
+    }
+
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    /// The same board played under `rules`.
+    pub fn with_rules(mut self, rules: GomokuRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> GomokuRules {
+        self.rules
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Cells in row-major order; index `row * width + col`. Row 0 is the top.
+    pub fn board(&self) -> &[Option<Player>] {
+        &self.board
+    }
+
+    /// For setting up positions. Leaves the status for the caller to recompute.
+    pub(crate) fn board_mut(&mut self) -> &mut [Option<Player>] {
+        &mut self.board
+    }
+
+    pub fn reset(&mut self) {
+        self.board.fill(None);
+        self.current_player = Player::X;
+        self.status = GameStatus::Ongoing;
+    }
+
+    /// Converts a row and column to a board index, if both are on the board.
+    pub fn index(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    pub fn play_move(&mut self, index: usize) -> Result<(), InvalidGameMoveError> {
+        self.validate_move(index)?;
+
+        self.board[index] = Some(self.current_player);
+        self.status = self.status_after(index);
+        self.current_player = self.current_player.next();
+        Ok(())
+    }
+
+    pub fn validate_move(&self, index: usize) -> Result<(), InvalidGameMoveError> {
+        if self.status != GameStatus::Ongoing {
+            return Err(InvalidGameMoveError::GameAlreadyWon);
+        }
+        match self.board.get(index) {
+            None => Err(InvalidGameMoveError::OutOfBounds),
+            Some(Some(_)) => Err(InvalidGameMoveError::SpaceOccupied),
+            Some(None) if self.gravity && self.drop_target(index % self.width) != Some(index) => {
+                Err(InvalidGameMoveError::Floating)
+            }
+            Some(None)
+                if self.rules == GomokuRules::Renju
+                    && self.current_player == Player::X
+                    && gomoku::is_forbidden(self, index) =>
+            {
+                Err(InvalidGameMoveError::Forbidden)
+            }
+            Some(None) => Ok(()),
+        }
+    }
+
+    /// The lowest empty cell of `col`, where a piece dropped there lands. `None` if the
+    /// column is full or off the board.
+    pub fn drop_target(&self, col: usize) -> Option<usize> {
+        (0..self.height)
+            .rev()
+            .filter_map(|row| self.index(row, col))
+            .find(|&index| self.board[index].is_none())
+    }
+
+    /// Drops a piece into `col` and returns the index of the cell it landed in. Works
+    /// with or without gravity.
+    pub fn play_column(&mut self, col: usize) -> Result<usize, InvalidGameMoveError> {
+        if col >= self.width {
+            return Err(InvalidGameMoveError::OutOfBounds);
+        }
+        let index = self
+            .drop_target(col)
+            .ok_or(InvalidGameMoveError::SpaceOccupied)?;
+        self.play_move(index)?;
+        Ok(index)
+    }
+
+    /// Every cell the current player may play, in index order. Under gravity that is
+    /// at most one per column.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        (0..self.board.len())
+            .filter(|&index| self.validate_move(index).is_ok())
+            .collect()
+    }
+
+    /// Status of the game given that `index` was the last move played.
+    fn status_after(&self, index: usize) -> GameStatus<Vec<usize>> {
+        if let Some(line) = self.line_through(index) {
+            return GameStatus::Win {
+                player: self.board[index].expect("last move is occupied"),
+                line,
+            };
+        }
+        if self.board.iter().all(|p| p.is_some()) {
+            return GameStatus::Draw;
+        }
+        GameStatus::Ongoing
+    }
+
+    /// Recomputes the status from scratch by checking lines through every occupied cell.
+    pub fn calculate_status(&self) -> GameStatus<Vec<usize>> {
+        for index in 0..self.board.len() {
+            if let Some(line) = self.line_through(index) {
+                return GameStatus::Win {
+                    player: self.board[index].expect("line cells are occupied"),
+                    line,
+                };
+            }
+        }
+        if self.board.iter().all(|p| p.is_some()) {
+            return GameStatus::Draw;
+        }
+        GameStatus::Ongoing
+    }
+
+    /// Returns the longest winning run of same-player cells passing through `index`:
+    /// at least `k` long, or exactly `k` where the rules say so.
+    pub fn line_through(&self, index: usize) -> Option<Vec<usize>> {
+        let player = self.board.get(index).copied().flatten()?;
+        DIRECTIONS
+            .iter()
+            .map(|&dir| self.run_through(index, dir))
+            .filter(|run| self.rules.wins(player, run.len(), self.k))
+            .max_by_key(|run| run.len())
+    }
+
+    /// Returns the cells of the unbroken same-player run through `index` along `dir`,
+    /// in ascending index order.
+    pub fn run_through(&self, index: usize, (dr, dc): (isize, isize)) -> Vec<usize> {
+        let Some(player) = self.board.get(index).copied().flatten() else {
+            return Vec::new();
+        };
+        let (row, col) = ((index / self.width) as isize, (index % self.width) as isize);
+
+        let mut run = vec![index];
+        for sign in [-1, 1] {
+            let (mut r, mut c) = (row + sign * dr, col + sign * dc);
+            while let Some(i) = self.signed_index(r, c) {
+                if self.board[i] != Some(player) {
+                    break;
+                }
+                run.push(i);
+                r += sign * dr;
+                c += sign * dc;
+            }
+        }
+        run.sort_unstable();
+        run
+    }
+
+    fn signed_index(&self, row: isize, col: isize) -> Option<usize> {
+        if row < 0 || col < 0 {
+            return None;
+        }
+        self.index(row as usize, col as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(engine: &mut MnkEngine, moves: &[usize]) {
+        for &m in moves {
+            engine.play_move(m).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_new_rejects_impossible_boards() {
+        assert!(MnkEngine::new(0, 3, 3).is_none());
+        assert!(MnkEngine::new(3, 3, 4).is_none());
+        assert!(MnkEngine::new(4, 1, 4).is_some());
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut engine = MnkEngine::new(4, 4, 3).unwrap();
+        assert_eq!(engine.play_move(16), Err(InvalidGameMoveError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_three_in_a_row_on_4x4() {
+        let mut engine = MnkEngine::new(4, 4, 3).unwrap();
+        // X: 5, 6, 7 (second row); O: 0, 1
+        play(&mut engine, &[5, 0, 6, 1, 7]);
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: vec![5, 6, 7]
+            }
+        );
+    }
+
+    #[test]
+    fn test_anti_diagonal_on_rectangular_board() {
+        // 5 wide, 4 tall; X plays (0,3), (1,2), (2,1), (3,0).
+        let mut engine = MnkEngine::new(5, 4, 4).unwrap();
+        play(&mut engine, &[3, 0, 7, 1, 11, 2, 15]);
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: vec![3, 7, 11, 15]
+            }
+        );
+    }
+
+    #[test]
+    fn test_gomoku_five_with_gap_filled_last() {
+        let mut engine = MnkEngine::gomoku();
+        let row = 7 * 15;
+        // X fills the middle of the run last.
+        play(&mut engine, &[row, 0, row + 1, 1, row + 3, 2, row + 4, 3]);
+        assert_eq!(engine.status, GameStatus::Ongoing);
+        engine.play_move(row + 2).unwrap();
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: (row..row + 5).collect()
+            }
+        );
+        assert_eq!(engine.calculate_status(), engine.status);
+    }
+
+    #[test]
+    fn test_connect_four_gravity() {
+        let mut engine = MnkEngine::connect_four();
+        assert_eq!(engine.legal_moves(), (35..42).collect::<Vec<_>>());
+        // Pieces stack up from the bottom row
+        assert_eq!(engine.play_column(3), Ok(38));
+        assert_eq!(engine.play_column(3), Ok(31));
+        assert_eq!(engine.play_move(10), Err(InvalidGameMoveError::Floating));
+        assert_eq!(
+            engine.play_column(7),
+            Err(InvalidGameMoveError::OutOfBounds)
+        );
+
+        // X builds a rising diagonal from the bottom left corner
+        for col in [0, 1, 1, 2, 2, 6, 2, 3] {
+            engine.play_column(col).unwrap();
+        }
+        assert_eq!(engine.status, GameStatus::Ongoing);
+        assert_eq!(engine.play_column(3), Ok(17));
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: vec![17, 23, 29, 35]
+            }
+        );
+    }
+
+    #[test]
+    fn test_full_column() {
+        let mut engine = MnkEngine::new(3, 2, 3).unwrap().with_gravity();
+        engine.play_column(0).unwrap();
+        engine.play_column(0).unwrap();
+        assert_eq!(engine.drop_target(0), None);
+        assert_eq!(
+            engine.play_column(0),
+            Err(InvalidGameMoveError::SpaceOccupied)
+        );
+        assert_eq!(engine.legal_moves(), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_draw_on_full_board() {
+        let mut engine = MnkEngine::new(3, 3, 3).unwrap();
+        play(&mut engine, &[0, 1, 2, 4, 3, 5, 7, 6, 8]);
+        assert_eq!(engine.status, GameStatus::Draw);
+    }
+}
@@ -0,0 +1,490 @@
+//! Notakto: tic-tac-toe on several boards where both players place X's.
+//!
+//! A board is dead once it holds three in a row, and nobody may play on it again.
+//! Whoever kills the last board loses. Since both players share the symbol, a
+//! finished game is reported like a misère one: the winner and the line the loser
+//! completed.
+//!
+//! Perfect play uses the misère quotient worked out by Plambeck and Whitehead in
+//! "The Secrets of Notakto". Every board maps to an element of a commutative monoid
+//! with 18 elements, a position is worth the product of its boards, and the player
+//! to move loses exactly when that product is one of four elements. So the best move
+//! is found with one multiplication per candidate instead of a search over every
+//! board at once.
+
+use crate::game::Game;
+use crate::rules::Rules;
+use crate::symmetry::Transform;
+use crate::{GameStatus, InvalidGameMoveError, Player, Position, WINNING_LINES};
+use std::sync::OnceLock;
+
+/// The most boards a game may be played on.
+pub const MAX_BOARDS: usize = 9;
+
+/// A Notakto move: an X in `cell` of board number `board`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct NotaktoMove {
+    pub board: usize,
+    pub cell: Position,
+}
+
+impl NotaktoMove {
+    pub fn new(board: usize, cell: u8) -> Option<Self> {
+        Some(Self {
+            board,
+            cell: Position::new(cell)?,
+        })
+    }
+}
+
+/// The rules of Notakto on a fixed number of boards. Each board holds X's only, so
+/// a cell is just filled or empty.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Notakto {
+    boards: usize,
+}
+
+/// The cells of a finished game's last line: the board it is on, then the cells.
+pub type NotaktoLine = (usize, [usize; 3]);
+
+pub type NotaktoGame = Game<Notakto>;
+
+impl Notakto {
+    /// Rules for `boards` boards, from one up to [`MAX_BOARDS`].
+    pub fn new(boards: usize) -> Option<Self> {
+        (1..=MAX_BOARDS)
+            .contains(&boards)
+            .then_some(Self { boards })
+    }
+
+    pub fn boards(&self) -> usize {
+        self.boards
+    }
+}
+
+fn mask(cells: &[bool; 9]) -> u16 {
+    cells
+        .iter()
+        .enumerate()
+        .filter(|&(_, &filled)| filled)
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+fn line_of(cells: &[bool; 9]) -> Option<[usize; 3]> {
+    let mask = mask(cells);
+    WINNING_LINES
+        .iter()
+        .find(|&&(line, _)| mask & line == line)
+        .map(|&(_, line)| line)
+}
+
+/// Whether a board holds three in a row and is out of play.
+pub fn is_dead(cells: &[bool; 9]) -> bool {
+    line_of(cells).is_some()
+}
+
+impl Rules for Notakto {
+    type Board = Vec<[bool; 9]>;
+    type Move = NotaktoMove;
+    type Line = NotaktoLine;
+
+    fn new_board(&self) -> Self::Board {
+        vec![[false; 9]; self.boards]
+    }
+
+    fn validate_move(
+        &self,
+        board: &Self::Board,
+        _player: Player,
+        mv: NotaktoMove,
+    ) -> Result<(), InvalidGameMoveError> {
+        let cells = board
+            .get(mv.board)
+            .ok_or(InvalidGameMoveError::OutOfBounds)?;
+        if is_dead(cells) {
+            Err(InvalidGameMoveError::WrongBoard)
+        } else if cells[mv.cell.to_index()] {
+            Err(InvalidGameMoveError::SpaceOccupied)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn legal_moves(&self, board: &Self::Board, _player: Player) -> Vec<NotaktoMove> {
+        board
+            .iter()
+            .enumerate()
+            .filter(|(_, cells)| !is_dead(cells))
+            .flat_map(|(index, cells)| {
+                (0..9)
+                    .filter(|&cell| !cells[cell as usize])
+                    .map(move |cell| NotaktoMove {
+                        board: index,
+                        cell: Position(cell),
+                    })
+            })
+            .collect()
+    }
+
+    fn apply_move(&self, board: &mut Self::Board, _player: Player, mv: NotaktoMove) {
+        board[mv.board][mv.cell.to_index()] = true;
+    }
+
+    /// Both players play X, so the loser is whoever placed the last X: the first
+    /// player if the number of X's is odd.
+    fn status(&self, board: &Self::Board) -> GameStatus<NotaktoLine> {
+        if !board.iter().all(is_dead) {
+            return GameStatus::Ongoing;
+        }
+        let placed: usize = board
+            .iter()
+            .map(|cells| cells.iter().filter(|&&filled| filled).count())
+            .sum();
+        let loser = if placed % 2 == 1 {
+            Player::X
+        } else {
+            Player::O
+        };
+        let (index, line) = board
+            .iter()
+            .enumerate()
+            .find_map(|(index, cells)| Some((index, line_of(cells)?)))
+            .expect("every board is dead");
+        GameStatus::Win {
+            player: loser.next(),
+            line: (index, line),
+        }
+    }
+
+    /// Reports the line the mover just completed rather than the first one found.
+    fn status_after(
+        &self,
+        board: &Self::Board,
+        player: Player,
+        mv: NotaktoMove,
+    ) -> GameStatus<NotaktoLine> {
+        match self.status(board) {
+            GameStatus::Win { .. } => GameStatus::Win {
+                player: player.next(),
+                line: (
+                    mv.board,
+                    line_of(&board[mv.board]).expect("the move killed it"),
+                ),
+            },
+            status => status,
+        }
+    }
+}
+
+/// An element a^a b^b c^c d^d of the Notakto quotient, kept in normal form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Element {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+}
+
+impl Element {
+    /// The value of a dead board, which changes nothing.
+    const ONE: Element = Element {
+        a: 0,
+        b: 0,
+        c: 0,
+        d: 0,
+    };
+
+    /// Reduces a product of generators with the quotient's relations:
+    /// a² = 1, b³ = b, b²c = c, c³ = ac², b²d = d, cd = ad and d² = c².
+    fn normalize(mut a: u8, mut b: u8, mut c: u8, mut d: u8) -> Self {
+        c += d / 2 * 2;
+        d %= 2;
+        if d == 1 {
+            a += c;
+            c = 0;
+        }
+        if c >= 3 {
+            a += c - 2;
+            c = 2;
+        }
+        let max_b = if c > 0 || d > 0 { 1 } else { 2 };
+        while b > max_b {
+            b -= 2;
+        }
+        Element { a: a % 2, b, c, d }
+    }
+
+    /// Parses a word such as "abb" or "cc"; "1" is the identity.
+    fn parse(word: &str) -> Self {
+        word.chars().fold(Element::ONE, |element, generator| {
+            let (a, b, c, d) = match generator {
+                'a' => (1, 0, 0, 0),
+                'b' => (0, 1, 0, 0),
+                'c' => (0, 0, 1, 0),
+                'd' => (0, 0, 0, 1),
+                _ => (0, 0, 0, 0),
+            };
+            element.times(Element { a, b, c, d })
+        })
+    }
+
+    fn times(self, other: Element) -> Element {
+        Element::normalize(
+            self.a + other.a,
+            self.b + other.b,
+            self.c + other.c,
+            self.d + other.d,
+        )
+    }
+
+    /// Whether the player to move loses: the position is one of a, b², bc or c².
+    fn is_losing(self) -> bool {
+        matches!(
+            (self.a, self.b, self.c, self.d),
+            (1, 0, 0, 0) | (0, 2, 0, 0) | (0, 1, 1, 0) | (0, 0, 2, 0)
+        )
+    }
+}
+
+/// The value of every live board up to rotation and reflection, by filled cells.
+const BOARD_VALUES: [(&[u8], &str); 46] = [
+    (&[], "c"),
+    (&[0], "1"),
+    (&[1], "1"),
+    (&[4], "cc"),
+    (&[0, 1], "d"),
+    (&[0, 2], "b"),
+    (&[1, 3], "a"),
+    (&[2, 3], "b"),
+    (&[0, 4], "b"),
+    (&[1, 4], "b"),
+    (&[3, 5], "a"),
+    (&[2, 6], "a"),
+    (&[0, 1, 3], "b"),
+    (&[0, 2, 3], "a"),
+    (&[1, 2, 3], "ad"),
+    (&[0, 1, 4], "ab"),
+    (&[0, 2, 4], "a"),
+    (&[1, 3, 4], "ab"),
+    (&[2, 3, 4], "a"),
+    (&[0, 3, 5], "ad"),
+    (&[1, 3, 5], "b"),
+    (&[0, 2, 6], "ab"),
+    (&[1, 2, 6], "ad"),
+    (&[0, 5, 6], "a"),
+    (&[1, 5, 6], "1"),
+    (&[0, 1, 3, 4], "a"),
+    (&[0, 2, 3, 4], "b"),
+    (&[1, 2, 3, 4], "b"),
+    (&[0, 1, 3, 5], "a"),
+    (&[0, 2, 3, 5], "b"),
+    (&[1, 2, 3, 6], "ab"),
+    (&[0, 1, 5, 6], "b"),
+    (&[0, 2, 5, 6], "b"),
+    (&[1, 2, 5, 6], "a"),
+    (&[1, 3, 5, 6], "ab"),
+    (&[2, 3, 5, 6], "a"),
+    (&[0, 4, 5, 6], "b"),
+    (&[1, 4, 5, 6], "b"),
+    (&[1, 3, 5, 7], "a"),
+    (&[0, 2, 6, 8], "a"),
+    (&[1, 2, 3, 5, 6], "b"),
+    (&[0, 1, 4, 5, 6], "a"),
+    (&[0, 1, 3, 5, 7], "b"),
+    (&[0, 2, 3, 5, 7], "a"),
+    (&[0, 2, 5, 6, 7], "a"),
+    (&[1, 2, 3, 5, 6, 7], "a"),
+];
+
+/// The value of every board, indexed by its filled cells as a bitmask. Dead boards
+/// are worth one.
+fn values() -> &'static [Element; 512] {
+    static VALUES: OnceLock<[Element; 512]> = OnceLock::new();
+    VALUES.get_or_init(|| {
+        let mut values = [Element::ONE; 512];
+        for (cells, word) in BOARD_VALUES {
+            let value = Element::parse(word);
+            for transform in Transform::ALL {
+                let mask = cells.iter().fold(0, |mask, &cell| {
+                    mask | 1 << transform.apply(Position(cell)).to_index()
+                });
+                values[mask] = value;
+            }
+        }
+        values
+    })
+}
+
+fn value_of(board: &[[bool; 9]]) -> Element {
+    let values = values();
+    board.iter().fold(Element::ONE, |value, cells| {
+        value.times(values[mask(cells) as usize])
+    })
+}
+
+/// Whether the player to move can force a win.
+pub fn to_move_wins(game: &NotaktoGame) -> bool {
+    match game.status() {
+        GameStatus::Ongoing => !value_of(game.board()).is_losing(),
+        _ => false,
+    }
+}
+
+/// A perfect move for the player to move, or `None` if the game is over. From a
+/// lost position it avoids killing a board while it can, to make the opponent work
+/// for the win.
+pub fn best_move(game: &NotaktoGame) -> Option<NotaktoMove> {
+    let moves = game.legal_moves();
+    let after = |mv: &NotaktoMove| {
+        let mut board = game.board().clone();
+        board[mv.board][mv.cell.to_index()] = true;
+        board
+    };
+    moves
+        .iter()
+        .find(|mv| value_of(&after(mv)).is_losing())
+        .or_else(|| moves.iter().find(|mv| !is_dead(&after(mv)[mv.board])))
+        .or(moves.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cells;
+    use std::collections::HashMap;
+
+    fn game(boards: &[&[u8]]) -> NotaktoGame {
+        let mut game = NotaktoGame::new(Notakto::new(boards.len()).unwrap());
+        // Both players place X's, so it doesn't matter who plays which
+        for (index, cells) in boards.iter().enumerate() {
+            for &cell in *cells {
+                game.play_move(NotaktoMove::new(index, cell).unwrap())
+                    .unwrap();
+            }
+        }
+        game
+    }
+
+    /// Whether the player to move wins, by searching every board at once.
+    fn search(boards: &mut Vec<u16>, memo: &mut HashMap<Vec<u16>, bool>) -> bool {
+        let live = |mask: u16| !WINNING_LINES.iter().any(|&(line, _)| mask & line == line);
+        // Boards that are rotations or reflections of each other play the same
+        let canonical = |mask: u16| {
+            Transform::ALL
+                .iter()
+                .map(|t| cells(mask).fold(0u16, |m, c| m | 1 << t.apply(Position(c)).to_index()))
+                .min()
+                .unwrap_or(mask)
+        };
+        boards.retain(|&mask| live(mask));
+        for mask in boards.iter_mut() {
+            *mask = canonical(*mask);
+        }
+        boards.sort();
+        if boards.is_empty() {
+            // The opponent killed the last board
+            return true;
+        }
+        if let Some(&wins) = memo.get(boards) {
+            return wins;
+        }
+        let wins = (0..boards.len()).any(|i| {
+            (0..9)
+                .filter(|cell| boards[i] & 1 << cell == 0)
+                .any(|cell| {
+                    let mut next = boards.clone();
+                    next[i] |= 1 << cell;
+                    !search(&mut next, memo)
+                })
+        });
+        memo.insert(boards.clone(), wins);
+        wins
+    }
+
+    #[test]
+    fn test_last_board_loses() {
+        let mut game = game(&[&[0, 1], &[4]]);
+        assert_eq!(game.play_move(NotaktoMove::new(0, 2).unwrap()), Ok(()));
+        assert_eq!(*game.status(), GameStatus::Ongoing);
+        assert_eq!(
+            game.validate_move(NotaktoMove::new(0, 5).unwrap()),
+            Err(InvalidGameMoveError::WrongBoard)
+        );
+        assert_eq!(
+            game.validate_move(NotaktoMove::new(2, 5).unwrap()),
+            Err(InvalidGameMoveError::OutOfBounds)
+        );
+        assert!(game.legal_moves().iter().all(|mv| mv.board == 1));
+
+        // O killed the first board and now has to finish the second
+        for cell in [0, 8] {
+            game.play_move(NotaktoMove::new(1, cell).unwrap()).unwrap();
+        }
+        assert_eq!(
+            *game.status(),
+            GameStatus::Win {
+                player: Player::X,
+                line: (1, [0, 4, 8])
+            }
+        );
+        // From the board alone the line is the first one found, but the winner agrees
+        assert!(matches!(
+            game.rules().status(game.board()),
+            GameStatus::Win {
+                player: Player::X,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_quotient_matches_search() {
+        // A sample of pairs of single-board positions, alone and with a third board
+        let live: Vec<u16> = (0..512u16)
+            .filter(|&mask| !WINNING_LINES.iter().any(|&(line, _)| mask & line == line))
+            .collect();
+        let values = values();
+        let mut memo = HashMap::new();
+        for (i, &first) in live.iter().enumerate() {
+            for &second in live.iter().skip(i).step_by(3) {
+                for third in [None, Some(0), Some(1 << 4), Some(0b11), Some(0b1010)] {
+                    let mut boards = vec![first, second];
+                    boards.extend(third);
+                    let value = boards
+                        .iter()
+                        .fold(Element::ONE, |v, &mask| v.times(values[mask as usize]));
+                    assert_eq!(
+                        search(&mut boards, &mut memo),
+                        !value.is_losing(),
+                        "{:09b} {:09b} {:?}",
+                        first,
+                        second,
+                        third
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_move_wins() {
+        // The first player wins on one board by taking the centre
+        let single = game(&[&[]]);
+        assert!(to_move_wins(&single));
+        assert_eq!(best_move(&single), NotaktoMove::new(0, 4));
+
+        // Perfect play from both sides: the side that starts in a won position wins
+        for boards in 1..=3 {
+            let mut game = NotaktoGame::new(Notakto::new(boards).unwrap());
+            let first_wins = to_move_wins(&game);
+            while let Some(mv) = best_move(&game) {
+                game.play_move(mv).unwrap();
+            }
+            let winner = if first_wins { Player::X } else { Player::O };
+            assert!(matches!(game.status(), GameStatus::Win { player, .. } if *player == winner));
+        }
+        assert!(Notakto::new(0).is_none());
+    }
+}
@@ -0,0 +1,477 @@
+//! Compact text notation for board positions, in the spirit of chess FEN.
+//!
+//! Rows are listed top to bottom and separated by `/`. Within a row, `X` and `O` are
+//! pieces and a number is a run of that many empty cells. The side to move follows
+//! after a space, then any rules that differ from the defaults:
+//!
+//! ```text
+//! X2/1O1/3 X                     classic 3x3 board, X to move
+//! X2/1O1/3 X misere              the same under misère rules
+//! 4,4,3 4/1X2/4/4 O              m,n,k board: width,height,k before the rows
+//! 7,6,4 7/7/7/7/7/3X3 O gravity  Connect Four, pieces fall to the bottom
+//! 15,15,5 15/.../15 X renju      Gomoku rules by name, joined to gravity by a comma
+//! ```
+//!
+//! Parsing checks that the position can occur in a real game: the piece counts match
+//! the side to move, at most one player has a line, a winning line could have been
+//! completed by the last move played, and under gravity no piece hangs in the air.
+
+use crate::gomoku::GomokuRules;
+use crate::mnk::MnkEngine;
+use crate::rules::RuleSet;
+use crate::{GameEngine, Player};
+
+/// The widest or tallest m,n,k board the notation accepts. Positions can come from
+/// untrusted clients, so the board size is bounded before anything is allocated.
+pub const MAX_SIDE: usize = 256;
+
+/// Represents errors that can occur when parsing a position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotationError {
+    /// The text does not follow the notation's syntax.
+    Malformed,
+    /// The rows do not match the board's width and height.
+    WrongSize,
+    /// X must have the same number of pieces as O, or exactly one more.
+    PieceCountMismatch,
+    /// The side to move does not follow from the piece counts.
+    WrongSideToMove,
+    /// Both players have a winning line.
+    BothPlayersWin,
+    /// The winning lines could not have been completed by a single final move.
+    UnreachableWin,
+    /// A piece sits above an empty cell on a board where pieces fall.
+    Floating,
+}
+
+impl GameEngine {
+    pub fn to_notation(&self) -> String {
+        let mut text = format!(
+            "{} {}",
+            write_rows(&self.board, 3),
+            player_char(self.current_player)
+        );
+        if !self.rules().is_standard() {
+            text.push(' ');
+            text.push_str(self.rules().name());
+        }
+        text
+    }
+
+    /// Parses a classic position. The move history of the result is empty.
+    pub fn from_notation(text: &str) -> Result<Self, NotationError> {
+        let ([rows, side], rules) = split_fields(text)?;
+        let rules = match rules {
+            Some(name) => RuleSet::from_name(name).ok_or(NotationError::Malformed)?,
+            None => RuleSet::Standard,
+        };
+        let mut mnk = MnkEngine::new(3, 3, 3).expect("3,3,3 is a valid board");
+        load_position(&mut mnk, rows, side)?;
+
+        let mut engine = GameEngine::with_rules(rules);
+        for (cell, &player) in engine.board.iter_mut().zip(mnk.board()) {
+            *cell = player;
+        }
+        engine.current_player = mnk.current_player;
+        engine.sync_from_board();
+        Ok(engine)
+    }
+}
+
+impl MnkEngine {
+    pub fn to_notation(&self) -> String {
+        let mut text = format!(
+            "{},{},{} {} {}",
+            self.width(),
+            self.height(),
+            self.k(),
+            write_rows(self.board(), self.width()),
+            player_char(self.current_player)
+        );
+        let mut options = Vec::new();
+        if self.has_gravity() {
+            options.push("gravity");
+        }
+        if self.rules() != GomokuRules::default() {
+            options.push(self.rules().name());
+        }
+        if !options.is_empty() {
+            text.push(' ');
+            text.push_str(&options.join(","));
+        }
+        text
+    }
+
+    pub fn from_notation(text: &str) -> Result<Self, NotationError> {
+        let ([dimensions, rows, side], options) = split_fields(text)?;
+        let dimensions: Vec<usize> = dimensions
+            .split(',')
+            .map(|n| n.parse().map_err(|_| NotationError::Malformed))
+            .collect::<Result<_, _>>()?;
+        let &[width, height, k] = dimensions.as_slice() else {
+            return Err(NotationError::Malformed);
+        };
+
+        if width > MAX_SIDE || height > MAX_SIDE || width.checked_mul(height).is_none() {
+            return Err(NotationError::WrongSize);
+        }
+        let mut engine = MnkEngine::new(width, height, k).ok_or(NotationError::WrongSize)?;
+        let mut rules = None;
+        for option in options.into_iter().flat_map(|options| options.split(',')) {
+            match (option, GomokuRules::from_name(option)) {
+                ("gravity", _) if !engine.has_gravity() => engine = engine.with_gravity(),
+                (_, Some(named)) if rules.is_none() => rules = Some(named),
+                _ => return Err(NotationError::Malformed),
+            }
+        }
+        engine = engine.with_rules(rules.unwrap_or_default());
+        load_position(&mut engine, rows, side)?;
+        Ok(engine)
+    }
+}
+
+/// Splits off the `N` required fields and the optional rules field after them.
+fn split_fields<const N: usize>(text: &str) -> Result<([&str; N], Option<&str>), NotationError> {
+    let mut fields: Vec<&str> = text.split_whitespace().collect();
+    let options = match fields.len() {
+        len if len == N + 1 => fields.pop(),
+        _ => None,
+    };
+    let fields = fields.try_into().map_err(|_| NotationError::Malformed)?;
+    Ok((fields, options))
+}
+
+fn player_char(player: Player) -> char {
+    match player {
+        Player::X => 'X',
+        Player::O => 'O',
+    }
+}
+
+fn write_rows(board: &[Option<Player>], width: usize) -> String {
+    let rows: Vec<String> = board
+        .chunks(width)
+        .map(|row| {
+            let mut text = String::new();
+            let mut empty = 0;
+            for cell in row {
+                match cell {
+                    None => empty += 1,
+                    Some(player) => {
+                        if empty > 0 {
+                            text.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        text.push(player_char(*player));
+                    }
+                }
+            }
+            if empty > 0 {
+                text.push_str(&empty.to_string());
+            }
+            text
+        })
+        .collect();
+    rows.join("/")
+}
+
+/// Fills `engine`'s board and side to move from the notation fields, checking that
+/// the position is reachable, and recomputes its status.
+fn load_position(engine: &mut MnkEngine, rows: &str, side: &str) -> Result<(), NotationError> {
+    let side = match side {
+        "X" => Player::X,
+        "O" => Player::O,
+        _ => return Err(NotationError::Malformed),
+    };
+
+    let rows: Vec<&str> = rows.split('/').collect();
+    if rows.len() != engine.height() {
+        return Err(NotationError::WrongSize);
+    }
+    let mut board = Vec::with_capacity(engine.board().len());
+    for row in rows {
+        board.extend(parse_row(row, engine.width())?);
+    }
+
+    engine.board_mut().copy_from_slice(&board);
+    engine.current_player = side;
+    if engine.has_gravity()
+        && (engine.width()..engine.board().len()).any(|index| {
+            engine.board()[index - engine.width()].is_some() && engine.board()[index].is_none()
+        })
+    {
+        return Err(NotationError::Floating);
+    }
+    check_reachable(engine)?;
+    engine.status = engine.calculate_status();
+    Ok(())
+}
+
+/// Parses one row, which must hold exactly `width` cells. Runs that would overflow
+/// the row are rejected before they are expanded.
+fn parse_row(row: &str, width: usize) -> Result<Vec<Option<Player>>, NotationError> {
+    let mut cells = Vec::with_capacity(width);
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        let run = match c {
+            'X' | 'O' => 1,
+            '1'..='9' => {
+                let mut run = c.to_digit(10).unwrap_or_default() as usize;
+                while let Some(digit) = chars.peek().and_then(|d| d.to_digit(10)) {
+                    run = run
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(digit as usize))
+                        .ok_or(NotationError::WrongSize)?;
+                    chars.next();
+                }
+                run
+            }
+            _ => return Err(NotationError::Malformed),
+        };
+        if run > width - cells.len() {
+            return Err(NotationError::WrongSize);
+        }
+        match c {
+            'X' => cells.push(Some(Player::X)),
+            'O' => cells.push(Some(Player::O)),
+            _ => cells.extend(std::iter::repeat_n(None, run)),
+        }
+    }
+    if cells.len() != width {
+        return Err(NotationError::WrongSize);
+    }
+    Ok(cells)
+}
+
+fn check_reachable(engine: &MnkEngine) -> Result<(), NotationError> {
+    let count = |player| {
+        engine
+            .board()
+            .iter()
+            .filter(|&&p| p == Some(player))
+            .count()
+    };
+    let (xs, os) = (count(Player::X), count(Player::O));
+
+    // X moves first, so X is to move exactly when the counts are equal
+    let side = match xs.checked_sub(os) {
+        Some(0) => Player::X,
+        Some(1) => Player::O,
+        _ => return Err(NotationError::PieceCountMismatch),
+    };
+    if engine.current_player != side {
+        return Err(NotationError::WrongSideToMove);
+    }
+
+    let x_wins = has_line(engine, Player::X);
+    let o_wins = has_line(engine, Player::O);
+    let winner = match (x_wins, o_wins) {
+        (true, true) => return Err(NotationError::BothPlayersWin),
+        (true, false) => Player::X,
+        (false, true) => Player::O,
+        (false, false) => return Ok(()),
+    };
+
+    // The winner must have made the last move, and removing some single piece of
+    // theirs has to undo every line (play stops as soon as a line is made). Under
+    // gravity, the last piece played is on top of its column.
+    if winner == side {
+        return Err(NotationError::UnreachableWin);
+    }
+    let mut before = engine.clone();
+    for index in 0..engine.board().len() {
+        let covered = engine.has_gravity()
+            && index >= engine.width()
+            && engine.board()[index - engine.width()].is_some();
+        if engine.board()[index] != Some(winner) || covered {
+            continue;
+        }
+        before.board_mut()[index] = None;
+        let undone = !has_line(&before, winner);
+        before.board_mut()[index] = Some(winner);
+        if undone {
+            return Ok(());
+        }
+    }
+    Err(NotationError::UnreachableWin)
+}
+
+fn has_line(engine: &MnkEngine, player: Player) -> bool {
+    (0..engine.board().len())
+        .any(|index| engine.board()[index] == Some(player) && engine.line_through(index).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameStatus, Position};
+
+    #[test]
+    fn test_round_trip_classic() {
+        let mut engine = GameEngine::new();
+        for &m in &[0, 4, 8] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        assert_eq!(engine.to_notation(), "X2/1O1/2X O");
+
+        let parsed = GameEngine::from_notation("X2/1O1/2X O").unwrap();
+        assert_eq!(parsed.board, engine.board);
+        assert_eq!(parsed.current_player, Player::O);
+        assert_eq!(GameEngine::new().to_notation(), "3/3/3 X");
+    }
+
+    #[test]
+    fn test_round_trip_misere() {
+        let mut engine = GameEngine::with_rules(RuleSet::Misere);
+        for &m in &[0, 3, 1, 4, 2] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        assert_eq!(engine.to_notation(), "XXX/OO1/3 O misere");
+
+        let parsed = GameEngine::from_notation("XXX/OO1/3 O misere").unwrap();
+        assert_eq!(parsed.rules(), RuleSet::Misere);
+        assert_eq!(parsed.status, engine.status);
+        assert_eq!(
+            GameEngine::from_notation("3/3/3 X lowball").err(),
+            Some(NotationError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_parse_o_win() {
+        let engine = GameEngine::from_notation("OOO/XX1/X2 X").unwrap();
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::O,
+                line: [0, 1, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_finished_game() {
+        let engine = GameEngine::from_notation("XXX/OO1/3 O").unwrap();
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 1, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_unreachable_positions() {
+        use NotationError::*;
+        let cases = [
+            ("X2/1O1/2 X", WrongSize),
+            ("X2/1O1/3/3 X", WrongSize),
+            ("X2/1Z1/3 X", Malformed),
+            ("X3/1O1/3 X", WrongSize),
+            ("X2/1O1/99999999999 X", WrongSize),
+            ("X2/1O1/99999999999999999999999 X", WrongSize),
+            ("X2/1O1/3", Malformed),
+            ("XX1/3/3 O", PieceCountMismatch),
+            ("O2/3/3 X", PieceCountMismatch),
+            ("X2/3/3 X", WrongSideToMove),
+            ("XXX/OOO/X2 O", BothPlayersWin),
+            // X won, but O kept playing
+            ("XXX/OO1/O2 X", UnreachableWin),
+        ];
+        for (text, error) in cases {
+            assert_eq!(
+                GameEngine::from_notation(text).err(),
+                Some(error),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip_mnk() {
+        let mut engine = MnkEngine::gomoku();
+        engine.play_move(7 * 15 + 7).unwrap();
+        engine.play_move(7 * 15 + 8).unwrap();
+        let text = engine.to_notation();
+        assert_eq!(
+            text,
+            "15,15,5 15/15/15/15/15/15/15/7XO6/15/15/15/15/15/15/15 X"
+        );
+        assert_eq!(MnkEngine::from_notation(&text).unwrap(), engine);
+    }
+
+    #[test]
+    fn test_round_trip_mnk_rules() {
+        let mut connect_four = MnkEngine::connect_four();
+        connect_four.play_column(3).unwrap();
+        let text = connect_four.to_notation();
+        assert_eq!(text, "7,6,4 7/7/7/7/7/3X3 O gravity");
+        assert_eq!(MnkEngine::from_notation(&text).unwrap(), connect_four);
+
+        let renju = MnkEngine::gomoku()
+            .with_gravity()
+            .with_rules(GomokuRules::Renju);
+        let text = renju.to_notation();
+        assert!(text.ends_with(" X gravity,renju"));
+        assert_eq!(MnkEngine::from_notation(&text).unwrap(), renju);
+
+        for options in ["gravity,gravity", "renju,standard", "sideways"] {
+            assert_eq!(
+                MnkEngine::from_notation(&format!("3,3,3 3/3/3 X {}", options)).err(),
+                Some(NotationError::Malformed),
+                "{}",
+                options
+            );
+        }
+    }
+
+    #[test]
+    fn test_gravity_positions() {
+        assert_eq!(
+            MnkEngine::from_notation("3,3,3 3/X2/3 O gravity").err(),
+            Some(NotationError::Floating)
+        );
+        assert!(MnkEngine::from_notation("3,3,3 3/X2/3 O").is_ok());
+
+        // X's bottom row is covered by O, so the last move cannot have made it
+        let text = "4,2,3 OOX1/XXXO O";
+        assert!(MnkEngine::from_notation(text).is_ok());
+        assert_eq!(
+            MnkEngine::from_notation(&format!("{} gravity", text)).err(),
+            Some(NotationError::UnreachableWin)
+        );
+    }
+
+    #[test]
+    fn test_mnk_two_separate_lines_are_unreachable() {
+        // Two disjoint X lines on a 5x3 board cannot both be made by one final move.
+        let text = "5,3,3 XXXO1/OO1OO/XXX2 O";
+        assert_eq!(
+            MnkEngine::from_notation(text).err(),
+            Some(NotationError::UnreachableWin)
+        );
+        assert_eq!(
+            MnkEngine::from_notation("0,3,3 3/3/3 X").err(),
+            Some(NotationError::WrongSize)
+        );
+    }
+
+    #[test]
+    fn test_mnk_rejects_oversized_boards() {
+        for text in [
+            "257,1,3 257 X",
+            "100000,100000,5 1 X",
+            "18446744073709551615,2,3 1/1 X",
+        ] {
+            assert_eq!(
+                MnkEngine::from_notation(text).err(),
+                Some(NotationError::WrongSize),
+                "{}",
+                text
+            );
+        }
+        assert!(MnkEngine::from_notation("256,1,3 256 X").is_ok());
+    }
+}
@@ -0,0 +1,441 @@
+//! Quantum tic-tac-toe, as devised by Allan Goff.
+//!
+//! Each move places a "spooky" mark in two squares at once: the mark will end up in
+//! exactly one of them. The marks entangle the squares, and when the entanglements
+//! close a cycle the other player chooses which of its two squares the mark that
+//! closed it ends up in. That choice collapses every mark in the cycle, and every
+//! mark tangled up with it, into a classical mark. Only classical marks make lines.
+//!
+//! A collapse can complete lines for both players at once. The line that was finished
+//! first, the one whose newest mark is older, scores a point and the other half a
+//! point.
+
+use crate::{InvalidGameMoveError, Player, Position, WINNING_LINES};
+
+/// A mark and the move that made it, counting from 1, so X's marks are odd.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct Mark {
+    pub player: Player,
+    pub turn: u8,
+}
+
+/// A mark entangled between two squares that has not collapsed into either yet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct SpookyMark {
+    pub mark: Mark,
+    pub squares: [Position; 2],
+}
+
+impl SpookyMark {
+    /// The square at the other end of the entanglement from `square`.
+    fn other(&self, square: Position) -> Position {
+        if self.squares[0] == square {
+            self.squares[1]
+        } else {
+            self.squares[0]
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum QuantumMove {
+    /// Place the current player's mark in two different squares.
+    Spooky(Position, Position),
+    /// Settle the cycle the last move closed by putting that move's mark in this square.
+    Collapse(Position),
+    /// Fill the only square left with a classical mark, as there is no second square
+    /// to entangle it with.
+    Classical(Position),
+}
+
+/// Outcome of a quantum game. Unlike [`GameStatus`](crate::GameStatus), both players
+/// can complete a line at the same time.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(tag = "type", content = "value"))]
+pub enum QuantumStatus {
+    /// `player` completed `line` first and scores a point. If the same collapse also
+    /// completed a line for the opponent, that line is `runner_up` and scores half a
+    /// point.
+    Win {
+        player: Player,
+        line: [usize; 3],
+        #[cfg_attr(feature = "wasm", serde(rename = "runnerUp"))]
+        runner_up: Option<[usize; 3]>,
+    },
+    Draw,
+    Ongoing,
+}
+
+impl QuantumStatus {
+    /// The points `player` scored: 1 for the winner, ½ for a runner-up line and 0
+    /// otherwise.
+    pub fn score(&self, player: Player) -> f32 {
+        match *self {
+            QuantumStatus::Win { player: winner, .. } if winner == player => 1.0,
+            QuantumStatus::Win {
+                runner_up: Some(_), ..
+            } => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub struct QuantumEngine {
+    /// The collapsed marks. A square with a classical mark is out of play.
+    pub classical: [Option<Mark>; 9],
+    /// The marks still entangled, oldest first.
+    pub spooky: Vec<SpookyMark>,
+    pub current_player: Player,
+    /// The mark that closed a cycle. The current player must collapse it before
+    /// placing a mark of their own.
+    pub cycle: Option<SpookyMark>,
+    pub status: QuantumStatus,
+    /// The number of the next mark.
+    turn: u8,
+}
+
+impl Default for QuantumEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuantumEngine {
+    pub fn new() -> Self {
+        Self {
+            classical: [None; 9],
+            spooky: Vec::new(),
+            current_player: Player::X,
+            cycle: None,
+            status: QuantumStatus::Ongoing,
+            turn: 1,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The spooky marks in `square`, oldest first.
+    pub fn marks_in(&self, square: Position) -> impl Iterator<Item = Mark> + use<'_> {
+        self.spooky
+            .iter()
+            .filter(move |spooky| spooky.squares.contains(&square))
+            .map(|spooky| spooky.mark)
+    }
+
+    /// The squares without a classical mark.
+    pub fn open_squares(&self) -> Vec<Position> {
+        (0..9)
+            .map(Position)
+            .filter(|square| self.classical[square.to_index()].is_none())
+            .collect()
+    }
+
+    pub fn validate_move(&self, mv: QuantumMove) -> Result<(), InvalidGameMoveError> {
+        if self.status != QuantumStatus::Ongoing {
+            return Err(InvalidGameMoveError::GameAlreadyWon);
+        }
+        let is_open = |square: Position| self.classical[square.to_index()].is_none();
+        match mv {
+            QuantumMove::Collapse(square) => match self.cycle {
+                Some(cycle) if cycle.squares.contains(&square) => Ok(()),
+                Some(_) => Err(InvalidGameMoveError::NotInCycle),
+                None => Err(InvalidGameMoveError::WrongMoveKind),
+            },
+            _ if self.cycle.is_some() => Err(InvalidGameMoveError::WrongMoveKind),
+            QuantumMove::Spooky(a, b) => {
+                if !is_open(a) || !is_open(b) {
+                    return Err(InvalidGameMoveError::SpaceOccupied);
+                }
+                if a == b {
+                    return Err(InvalidGameMoveError::WrongMoveKind);
+                }
+                Ok(())
+            }
+            QuantumMove::Classical(square) => {
+                if !is_open(square) {
+                    return Err(InvalidGameMoveError::SpaceOccupied);
+                }
+                if self.open_squares().len() != 1 {
+                    return Err(InvalidGameMoveError::WrongMoveKind);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The moves the current player may play: the two squares of a pending collapse,
+    /// the last open square, or every pair of open squares. Empty once the game is
+    /// over.
+    pub fn legal_moves(&self) -> Vec<QuantumMove> {
+        if self.status != QuantumStatus::Ongoing {
+            return Vec::new();
+        }
+        if let Some(cycle) = self.cycle {
+            return cycle.squares.map(QuantumMove::Collapse).to_vec();
+        }
+        let open = self.open_squares();
+        if let [square] = open[..] {
+            return vec![QuantumMove::Classical(square)];
+        }
+        let mut moves = Vec::new();
+        for (i, &a) in open.iter().enumerate() {
+            for &b in &open[i + 1..] {
+                moves.push(QuantumMove::Spooky(a, b));
+            }
+        }
+        moves
+    }
+
+    /// Play a move. A collapse is part of the collapsing player's turn, so they stay
+    /// the current player and go on to place a mark.
+    pub fn play_move(&mut self, mv: QuantumMove) -> Result<(), InvalidGameMoveError> {
+        self.validate_move(mv)?;
+
+        let mark = Mark {
+            player: self.current_player,
+            turn: self.turn,
+        };
+        match mv {
+            QuantumMove::Spooky(a, b) => {
+                let spooky = SpookyMark {
+                    mark,
+                    squares: [a, b],
+                };
+                // Entangling two squares that are already connected closes a cycle
+                if self.connected(a, b) {
+                    self.cycle = Some(spooky);
+                }
+                self.spooky.push(spooky);
+                self.turn += 1;
+                self.current_player = self.current_player.next();
+            }
+            QuantumMove::Collapse(square) => {
+                if let Some(cycle) = self.cycle.take() {
+                    self.collapse(cycle, square);
+                }
+                self.status = self.calculate_status();
+            }
+            QuantumMove::Classical(square) => {
+                self.classical[square.to_index()] = Some(mark);
+                self.turn += 1;
+                self.status = self.calculate_status();
+                self.current_player = self.current_player.next();
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a chain of entanglements links square `a` to square `b`.
+    fn connected(&self, a: Position, b: Position) -> bool {
+        let mut seen = vec![a];
+        let mut frontier = vec![a];
+        while let Some(square) = frontier.pop() {
+            if square == b {
+                return true;
+            }
+            for spooky in self.spooky.iter().filter(|s| s.squares.contains(&square)) {
+                let next = spooky.other(square);
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    frontier.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Put `spooky`'s mark in `square`, forcing every other mark there into its other
+    /// square, and so on until nothing tangled up with the cycle is left.
+    fn collapse(&mut self, spooky: SpookyMark, square: Position) {
+        self.spooky.retain(|s| s.mark != spooky.mark);
+        let mut settled = vec![(spooky.mark, square)];
+        while let Some((mark, square)) = settled.pop() {
+            self.classical[square.to_index()] = Some(mark);
+            let (forced, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.spooky)
+                .into_iter()
+                .partition(|s| s.squares.contains(&square));
+            self.spooky = rest;
+            settled.extend(forced.iter().map(|s| (s.mark, s.other(square))));
+        }
+    }
+
+    /// Status from the classical marks. A line is as old as its newest mark, and each
+    /// player's oldest line is the one that counts.
+    pub fn calculate_status(&self) -> QuantumStatus {
+        let mut first: [Option<(u8, [usize; 3])>; 2] = [None; 2];
+        for &(_, line) in WINNING_LINES.iter() {
+            let [Some(a), Some(b), Some(c)] = line.map(|i| self.classical[i]) else {
+                continue;
+            };
+            if a.player != b.player || b.player != c.player {
+                continue;
+            }
+            let completed = a.turn.max(b.turn).max(c.turn);
+            let slot = match a.player {
+                Player::X => &mut first[0],
+                Player::O => &mut first[1],
+            };
+            if slot.is_none_or(|(turn, _)| completed < turn) {
+                *slot = Some((completed, line));
+            }
+        }
+
+        match first {
+            [Some((x_turn, x_line)), Some((o_turn, o_line))] => {
+                let (player, line, runner_up) = if x_turn < o_turn {
+                    (Player::X, x_line, o_line)
+                } else {
+                    (Player::O, o_line, x_line)
+                };
+                QuantumStatus::Win {
+                    player,
+                    line,
+                    runner_up: Some(runner_up),
+                }
+            }
+            [Some((_, line)), None] => QuantumStatus::Win {
+                player: Player::X,
+                line,
+                runner_up: None,
+            },
+            [None, Some((_, line))] => QuantumStatus::Win {
+                player: Player::O,
+                line,
+                runner_up: None,
+            },
+            [None, None] if self.classical.iter().all(Option::is_some) => QuantumStatus::Draw,
+            [None, None] => QuantumStatus::Ongoing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spooky(a: u8, b: u8) -> QuantumMove {
+        QuantumMove::Spooky(Position(a), Position(b))
+    }
+
+    fn mark(player: Player, turn: u8) -> Option<Mark> {
+        Some(Mark { player, turn })
+    }
+
+    #[test]
+    fn test_cycle_collapses() {
+        let mut engine = QuantumEngine::new();
+        engine.play_move(spooky(0, 1)).unwrap();
+        engine.play_move(spooky(1, 2)).unwrap();
+        assert_eq!(engine.cycle, None);
+        assert_eq!(engine.marks_in(Position(1)).count(), 2);
+
+        // X closes the cycle 0-1-2, so O chooses where X's third mark goes
+        engine.play_move(spooky(2, 0)).unwrap();
+        assert_eq!(engine.current_player, Player::O);
+        assert!(engine.cycle.is_some());
+        assert_eq!(
+            engine.play_move(spooky(4, 5)),
+            Err(InvalidGameMoveError::WrongMoveKind)
+        );
+        assert_eq!(
+            engine.play_move(QuantumMove::Collapse(Position(1))),
+            Err(InvalidGameMoveError::NotInCycle)
+        );
+        assert_eq!(engine.legal_moves().len(), 2);
+
+        engine
+            .play_move(QuantumMove::Collapse(Position(0)))
+            .unwrap();
+        assert_eq!(
+            engine.classical[..3],
+            [mark(Player::X, 3), mark(Player::X, 1), mark(Player::O, 2)]
+        );
+        assert!(engine.spooky.is_empty());
+        assert_eq!(engine.current_player, Player::O);
+        assert_eq!(
+            engine.play_move(spooky(0, 4)),
+            Err(InvalidGameMoveError::SpaceOccupied)
+        );
+        // Six open squares left to pair up
+        assert_eq!(engine.legal_moves().len(), 15);
+        engine.play_move(spooky(3, 4)).unwrap();
+        assert_eq!(engine.marks_in(Position(4)).next(), mark(Player::O, 4));
+    }
+
+    #[test]
+    fn test_collapse_spreads_to_attached_marks() {
+        let mut engine = QuantumEngine::new();
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (0, 1)] {
+            engine.play_move(spooky(a, b)).unwrap();
+        }
+        // The marks in 2 and 3 hang off the cycle between 0 and 1 and collapse with it
+        engine
+            .play_move(QuantumMove::Collapse(Position(1)))
+            .unwrap();
+        assert_eq!(
+            engine.classical[..4],
+            [
+                mark(Player::X, 1),
+                mark(Player::O, 4),
+                mark(Player::O, 2),
+                mark(Player::X, 3)
+            ]
+        );
+        assert!(engine.spooky.is_empty());
+    }
+
+    #[test]
+    fn test_simultaneous_lines_score_half_a_point() {
+        let mut engine = QuantumEngine::new();
+        engine.classical = [
+            mark(Player::X, 1),
+            mark(Player::X, 3),
+            mark(Player::X, 7),
+            mark(Player::O, 2),
+            mark(Player::O, 4),
+            mark(Player::O, 6),
+            None,
+            None,
+            None,
+        ];
+        let status = engine.calculate_status();
+        assert_eq!(
+            status,
+            QuantumStatus::Win {
+                player: Player::O,
+                line: [3, 4, 5],
+                runner_up: Some([0, 1, 2])
+            }
+        );
+        assert_eq!(status.score(Player::O), 1.0);
+        assert_eq!(status.score(Player::X), 0.5);
+    }
+
+    #[test]
+    fn test_last_square_is_classical() {
+        let mut engine = QuantumEngine::new();
+        let players = [0, 1, 0, 0, 1, 1, 1, 0].map(|p| [Player::X, Player::O][p]);
+        for (i, player) in players.into_iter().enumerate() {
+            engine.classical[i] = mark(player, i as u8 + 1);
+        }
+        engine.turn = 9;
+
+        assert_eq!(
+            engine.legal_moves(),
+            vec![QuantumMove::Classical(Position(8))]
+        );
+        engine
+            .play_move(QuantumMove::Classical(Position(8)))
+            .unwrap();
+        assert_eq!(engine.status, QuantumStatus::Draw);
+        assert_eq!(engine.status.score(Player::X), 0.0);
+    }
+}
@@ -0,0 +1,407 @@
+//! Qubic: tic-tac-toe on a 4x4x4 cube, where four in a row wins.
+//!
+//! The cube has 76 winning lines: the rows, columns and pillars, the diagonals of
+//! every plane, and the four diagonals through the middle of the cube. Its 64 cells
+//! fit a `u64` exactly, so the board is a pair of bitboards like the classic engine's.
+//!
+//! The game tree is far too big for minimax. [`best_move`] instead relies on
+//! threat-space search: it looks for a chain of threes that each force a block and
+//! end in two threats at once, and makes sure not to allow the opponent one.
+
+use crate::game::Game;
+use crate::rules::Rules;
+use crate::{GameStatus, InvalidGameMoveError, Player, bit_index};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// A cell of the cube, numbered layer by layer: `16 * layer + 4 * row + col`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CubePosition(u8);
+
+impl CubePosition {
+    pub fn new(layer: u8, row: u8, col: u8) -> Option<Self> {
+        (layer < 4 && row < 4 && col < 4).then_some(Self(16 * layer + 4 * row + col))
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        (index < 64).then_some(Self(index as u8))
+    }
+
+    pub fn layer(&self) -> u8 {
+        self.0 / 16
+    }
+
+    pub fn row(&self) -> u8 {
+        self.0 / 4 % 4
+    }
+
+    pub fn col(&self) -> u8 {
+        self.0 % 4
+    }
+
+    pub fn to_index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The cube as one bitmask per player (bit `i` is cell `i`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct QubicBoard {
+    bits: [u64; 2],
+}
+
+impl QubicBoard {
+    pub fn get(&self, pos: CubePosition) -> Option<Player> {
+        let bit = 1 << pos.to_index();
+        if self.bits[0] & bit != 0 {
+            Some(Player::X)
+        } else if self.bits[1] & bit != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    fn cells_of(&self, player: Player) -> u64 {
+        self.bits[bit_index(player)]
+    }
+
+    fn empty(&self) -> u64 {
+        !(self.bits[0] | self.bits[1])
+    }
+
+    fn place(&mut self, player: Player, index: usize) {
+        self.bits[bit_index(player)] |= 1 << index;
+    }
+
+    /// The cells where `player` would complete a line.
+    fn winning_cells(&self, player: Player) -> u64 {
+        let (mine, theirs) = (self.cells_of(player), self.cells_of(player.next()));
+        lines()
+            .iter()
+            .filter(|&&(mask, _)| (mine & mask).count_ones() == 3 && theirs & mask == 0)
+            .fold(0, |cells, &(mask, _)| cells | (mask & !mine))
+    }
+}
+
+/// The winning lines as bitmasks with their cells, in the order they are generated.
+pub(crate) fn lines() -> &'static [(u64, [usize; 4])] {
+    static LINES: OnceLock<Vec<(u64, [usize; 4])>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let mut lines = Vec::new();
+        // One direction of each opposite pair, so every line is found once
+        let directions = (0..27)
+            .map(|d| [d / 9 - 1, d / 3 % 3 - 1, d % 3 - 1])
+            .filter(|step| step.iter().find(|&&s| s != 0) == Some(&1));
+        for step in directions {
+            for start in 0..64 {
+                let start = [start / 16, start / 4 % 4, start % 4];
+                // The far end must still be inside the cube
+                let fits = (0..3).all(|axis| (0..4).contains(&(start[axis] + 3 * step[axis])));
+                if !fits {
+                    continue;
+                }
+                let cells = [0, 1, 2, 3].map(|i| {
+                    let [layer, row, col] = [0, 1, 2].map(|axis| start[axis] + i * step[axis]);
+                    (16 * layer + 4 * row + col) as usize
+                });
+                let mask = cells.iter().fold(0, |mask, &cell| mask | 1 << cell);
+                lines.push((mask, cells));
+            }
+        }
+        lines
+    })
+}
+
+/// The rules of Qubic.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Qubic;
+
+pub type QubicGame = Game<Qubic>;
+
+impl Rules for Qubic {
+    type Board = QubicBoard;
+    type Move = CubePosition;
+    type Line = [usize; 4];
+
+    fn new_board(&self) -> QubicBoard {
+        QubicBoard::default()
+    }
+
+    fn validate_move(
+        &self,
+        board: &QubicBoard,
+        _player: Player,
+        pos: CubePosition,
+    ) -> Result<(), InvalidGameMoveError> {
+        match board.get(pos) {
+            Some(_) => Err(InvalidGameMoveError::SpaceOccupied),
+            None => Ok(()),
+        }
+    }
+
+    fn legal_moves(&self, board: &QubicBoard, _player: Player) -> Vec<CubePosition> {
+        (0..64)
+            .filter(|&i| board.empty() & 1 << i != 0)
+            .map(CubePosition)
+            .collect()
+    }
+
+    fn apply_move(&self, board: &mut QubicBoard, player: Player, pos: CubePosition) {
+        board.place(player, pos.to_index());
+    }
+
+    fn status(&self, board: &QubicBoard) -> GameStatus<[usize; 4]> {
+        for player in [Player::X, Player::O] {
+            let mine = board.cells_of(player);
+            if let Some(&(_, line)) = lines().iter().find(|&&(mask, _)| mine & mask == mask) {
+                return GameStatus::Win { player, line };
+            }
+        }
+        if board.empty() == 0 {
+            GameStatus::Draw
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+}
+
+/// Upper bound on the positions one threat-space search visits.
+const THREAT_SEARCH_NODES: usize = 4000;
+
+/// How many of the heuristically best moves are checked for letting the opponent
+/// start a winning threat sequence.
+const DEFENCE_CANDIDATES: usize = 12;
+
+/// Searches for a win for `attacker`, who is to move, made only of threats: each
+/// move makes three in a line so the defender has to block the fourth cell, until a
+/// move makes two threes at once. Returns the first move of such a sequence.
+///
+/// The search is sound but not complete. It gives up on sequences where a forced
+/// block makes a three for the defender, and after a fixed number of positions.
+pub fn threat_search(board: &QubicBoard, attacker: Player) -> Option<CubePosition> {
+    if let Some(cell) = first_cell(board.winning_cells(attacker)) {
+        return Some(cell);
+    }
+    // A three for the defender has to be blocked first, which may not be a threat
+    if board.winning_cells(attacker.next()) != 0 {
+        return None;
+    }
+    let mut search = ThreatSearch {
+        attacker,
+        visited: HashSet::new(),
+    };
+    search.find(board).and_then(CubePosition::from_index)
+}
+
+struct ThreatSearch {
+    attacker: Player,
+    visited: HashSet<QubicBoard>,
+}
+
+impl ThreatSearch {
+    fn find(&mut self, board: &QubicBoard) -> Option<usize> {
+        let (attacker, defender) = (self.attacker, self.attacker.next());
+        let (mine, theirs) = (board.cells_of(attacker), board.cells_of(defender));
+
+        // Moves that turn a two into a three
+        let candidates = lines()
+            .iter()
+            .filter(|&&(mask, _)| (mine & mask).count_ones() == 2 && theirs & mask == 0)
+            .fold(0, |cells, &(mask, _)| cells | (mask & !mine));
+
+        for index in bit_indices(candidates) {
+            let mut next = *board;
+            next.place(attacker, index);
+            let threats = next.winning_cells(attacker);
+            if threats.count_ones() >= 2 {
+                return Some(index);
+            }
+
+            // The defender's block is forced. It must not leave them a three of their own
+            next.place(defender, threats.trailing_zeros() as usize);
+            if next.winning_cells(defender) != 0 {
+                continue;
+            }
+            if self.visited.len() >= THREAT_SEARCH_NODES || !self.visited.insert(next) {
+                continue;
+            }
+            if self.find(&next).is_some() {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+fn first_cell(cells: u64) -> Option<CubePosition> {
+    CubePosition::from_index(cells.trailing_zeros() as usize)
+}
+
+fn bit_indices(mut bits: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let index = bits.trailing_zeros() as usize;
+        bits &= bits.wrapping_sub(1);
+        (index < 64).then_some(index)
+    })
+}
+
+/// How good an empty cell is for `player`: lines through it count for more the more
+/// of them `player` holds, and for blocking the more of them the opponent holds.
+fn cell_score(board: &QubicBoard, player: Player, index: usize) -> u32 {
+    const OWN: [u32; 4] = [1, 6, 36, 0];
+    const BLOCK: [u32; 4] = [1, 5, 30, 0];
+    let (mine, theirs) = (board.cells_of(player), board.cells_of(player.next()));
+    lines()
+        .iter()
+        .filter(|&&(mask, _)| mask & 1 << index != 0)
+        .map(
+            |&(mask, _)| match ((mine & mask).count_ones(), (theirs & mask).count_ones()) {
+                (own, 0) => OWN[own as usize],
+                (0, other) => BLOCK[other as usize],
+                _ => 0,
+            },
+        )
+        .sum()
+}
+
+/// A move for the player to move, or `None` if the game is over.
+///
+/// Wins at once or blocks the opponent's three if it can. Otherwise it plays a
+/// threat sequence that forces a win, or else the best cell by [`cell_score`] that
+/// does not let the opponent start one.
+pub fn best_move(game: &QubicGame) -> Option<CubePosition> {
+    if *game.status() != GameStatus::Ongoing {
+        return None;
+    }
+    let board = *game.board();
+    let player = game.current_player();
+
+    if let Some(pos) = threat_search(&board, player) {
+        return Some(pos);
+    }
+    if let Some(pos) = first_cell(board.winning_cells(player.next())) {
+        return Some(pos);
+    }
+
+    let mut candidates: Vec<usize> = bit_indices(board.empty()).collect();
+    candidates.sort_by_key(|&index| std::cmp::Reverse(cell_score(&board, player, index)));
+    let safe = candidates.iter().take(DEFENCE_CANDIDATES).find(|&&index| {
+        let mut next = board;
+        next.place(player, index);
+        threat_search(&next, player.next()).is_none()
+    });
+    safe.or(candidates.first())
+        .and_then(|&index| CubePosition::from_index(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut QubicGame, moves: &[(u8, u8, u8)]) {
+        for &(layer, row, col) in moves {
+            game.play_move(CubePosition::new(layer, row, col).unwrap())
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines().len(), 76);
+        // The corners and the inner cube lie on seven lines, every other cell on four
+        for index in 0..64 {
+            let count = lines()
+                .iter()
+                .filter(|(mask, _)| mask & 1 << index != 0)
+                .count();
+            let pos = CubePosition::from_index(index).unwrap();
+            let inner = |c: u8| c == 1 || c == 2;
+            let special = [pos.layer(), pos.row(), pos.col()]
+                .iter()
+                .all(|&c| inner(c))
+                || [pos.layer(), pos.row(), pos.col()]
+                    .iter()
+                    .all(|&c| !inner(c));
+            assert_eq!(count, if special { 7 } else { 4 }, "cell {}", index);
+        }
+    }
+
+    #[test]
+    fn test_space_diagonal_wins() {
+        let mut game = QubicGame::default();
+        play(
+            &mut game,
+            &[
+                (0, 0, 0),
+                (0, 0, 1),
+                (1, 1, 1),
+                (0, 0, 2),
+                (2, 2, 2),
+                (0, 1, 0),
+            ],
+        );
+        assert_eq!(*game.status(), GameStatus::Ongoing);
+        play(&mut game, &[(3, 3, 3)]);
+        assert_eq!(
+            *game.status(),
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 21, 42, 63]
+            }
+        );
+        assert_eq!(game.legal_moves(), Vec::new());
+    }
+
+    #[test]
+    fn test_threat_search_finds_forced_win() {
+        // X holds two cells on each of three lines, arranged so that forcing threes
+        // lead to a double threat O cannot stop
+        let mut game = QubicGame::default();
+        play(
+            &mut game,
+            &[
+                (0, 0, 0),
+                (3, 3, 0),
+                (0, 1, 1),
+                (3, 3, 1),
+                (0, 0, 3),
+                (3, 0, 3),
+            ],
+        );
+        let board = *game.board();
+        assert_eq!(board.winning_cells(Player::X), 0);
+        let first = threat_search(&board, Player::X).expect("X has a forced win");
+
+        // Follow the threats, with O always blocking, until X completes a line
+        let mut game = game.clone();
+        game.play_move(first).unwrap();
+        while *game.status() == GameStatus::Ongoing {
+            let block = first_cell(game.board().winning_cells(Player::X)).unwrap();
+            game.play_move(block).unwrap();
+            let attack = threat_search(game.board(), Player::X).unwrap();
+            game.play_move(attack).unwrap();
+        }
+        assert!(matches!(
+            game.status(),
+            GameStatus::Win {
+                player: Player::X,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_best_move_wins_and_blocks() {
+        let mut game = QubicGame::default();
+        play(
+            &mut game,
+            &[(0, 0, 0), (1, 0, 0), (0, 0, 1), (1, 0, 1), (0, 0, 2)],
+        );
+        // O must block X's row rather than extend its own
+        assert_eq!(best_move(&game), CubePosition::new(0, 0, 3));
+        // If O extends its own row instead, X wins rather than blocking
+        play(&mut game, &[(1, 0, 2)]);
+        assert_eq!(best_move(&game), CubePosition::new(0, 0, 3));
+    }
+}
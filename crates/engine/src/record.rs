@@ -0,0 +1,470 @@
+//! Game records: a PGN-style text format for archiving and replaying classic games.
+//!
+//! A record is a block of `[Tag "value"]` lines followed by a blank line and the
+//! numbered move list, ending with the result:
+//!
+//! ```text
+//! [Variant "classic"]
+//! [X "Alice"]
+//! [O "Bob"]
+//! [Date "2026.10.17"]
+//! [Result "1-0"]
+//!
+//! 1. 4 0 2. 2 6 3. 3 5 4. 1 7 5. 8 1-0
+//! ```
+//!
+//! Moves are board indices 0-8, and only classic and misère games can be recorded.
+//! Within a tag value `\\`, `\"`, `\n` and `\r` stand for a backslash, a quote and
+//! line breaks, so names cannot break out of their tag. Unknown tags are ignored so
+//! records can carry extra annotations. A game lost by forfeit, such as a bot playing
+//! an illegal move, carries `[Termination "forfeit"]` and a win for the other side
+//! although play stopped early.
+
+use crate::rules::RuleSet;
+use crate::{GameEngine, GameStatus, InvalidGameMoveError, Player, Position};
+
+/// Variant name for games under standard rules.
+pub const CLASSIC_VARIANT: &str = "classic";
+/// Variant name for games where three in a row loses.
+pub const MISERE_VARIANT: &str = "misere";
+
+/// The rules a variant name is played under, if it can be replayed.
+pub fn variant_rules(variant: &str) -> Option<RuleSet> {
+    match variant {
+        CLASSIC_VARIANT => Some(RuleSet::Standard),
+        MISERE_VARIANT => Some(RuleSet::Misere),
+        _ => None,
+    }
+}
+
+fn variant_name(rules: RuleSet) -> &'static str {
+    match rules {
+        RuleSet::Standard => CLASSIC_VARIANT,
+        RuleSet::Misere => MISERE_VARIANT,
+    }
+}
+
+/// Value of the `Termination` tag for a game lost by forfeit.
+pub const FORFEIT_TERMINATION: &str = "forfeit";
+
+/// How a recorded game ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    XWins,
+    OWins,
+    Draw,
+    /// The game was saved before it finished.
+    Unfinished,
+}
+
+impl GameResult {
+    pub fn from_status(status: GameStatus) -> Self {
+        match status {
+            GameStatus::Win {
+                player: Player::X, ..
+            } => GameResult::XWins,
+            GameStatus::Win {
+                player: Player::O, ..
+            } => GameResult::OWins,
+            GameStatus::Draw => GameResult::Draw,
+            GameStatus::Ongoing => GameResult::Unfinished,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameResult::XWins => "1-0",
+            GameResult::OWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unfinished => "*",
+        }
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        match text {
+            "1-0" => Some(GameResult::XWins),
+            "0-1" => Some(GameResult::OWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unfinished),
+            _ => None,
+        }
+    }
+}
+
+/// Represents errors that can occur when parsing a game record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// A tag line is not of the form `[Name "value"]`.
+    MalformedTag(String),
+    /// A token in the move list is not a move number, a position or a result.
+    MalformedMove(String),
+    /// The record is for a variant that cannot be replayed.
+    UnsupportedVariant(String),
+    /// The move at this (zero-based) index is not legal in the replayed game.
+    IllegalMove(usize, InvalidGameMoveError),
+    /// The stated result does not match the result of replaying the moves.
+    ResultMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// The rules the game was played under, written as the `Variant` tag.
+    pub rules: RuleSet,
+    pub x_player: String,
+    pub o_player: String,
+    /// Date the game was played, as `YYYY.MM.DD`. Unknown parts are `??`.
+    pub date: String,
+    pub result: GameResult,
+    /// Whether the loser forfeited before the game was over, so `result` does not
+    /// follow from the moves.
+    pub forfeit: bool,
+    pub moves: Vec<Position>,
+}
+
+impl GameRecord {
+    /// Records the moves played so far in `engine`.
+    pub fn from_engine(engine: &GameEngine, x_player: &str, o_player: &str, date: &str) -> Self {
+        Self {
+            rules: engine.rules(),
+            x_player: x_player.to_string(),
+            o_player: o_player.to_string(),
+            date: date.to_string(),
+            result: GameResult::from_status(engine.status),
+            forfeit: false,
+            moves: engine.history(),
+        }
+    }
+
+    /// The same record, ended by `loser` forfeiting: the other player wins.
+    pub fn forfeited_by(mut self, loser: Player) -> Self {
+        self.result = match loser {
+            Player::X => GameResult::OWins,
+            Player::O => GameResult::XWins,
+        };
+        self.forfeit = true;
+        self
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (name, value) in [
+            ("Variant", variant_name(self.rules)),
+            ("X", &self.x_player),
+            ("O", &self.o_player),
+            ("Date", &self.date),
+            ("Result", self.result.as_str()),
+        ] {
+            text.push_str(&format!("[{} \"{}\"]\n", name, escape(value)));
+        }
+        if self.forfeit {
+            text.push_str(&format!("[Termination \"{}\"]\n", FORFEIT_TERMINATION));
+        }
+        text.push('\n');
+
+        let mut tokens = Vec::new();
+        for (turn, pair) in self.moves.chunks(2).enumerate() {
+            tokens.push(format!("{}.", turn + 1));
+            tokens.extend(pair.iter().map(|pos| pos.to_index().to_string()));
+        }
+        tokens.push(self.result.as_str().to_string());
+        text.push_str(&tokens.join(" "));
+        text.push('\n');
+        text
+    }
+
+    /// Parses a record and checks that its moves replay legally to the stated result.
+    pub fn parse(text: &str) -> Result<Self, RecordError> {
+        let mut record = GameRecord {
+            rules: RuleSet::Standard,
+            x_player: "?".to_string(),
+            o_player: "?".to_string(),
+            date: "????.??.??".to_string(),
+            result: GameResult::Unfinished,
+            forfeit: false,
+            moves: Vec::new(),
+        };
+        let mut stated_result = None;
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if line.starts_with('[') {
+                let (name, value) = parse_tag(line)?;
+                match name {
+                    "Variant" => {
+                        record.rules =
+                            variant_rules(&value).ok_or(RecordError::UnsupportedVariant(value))?
+                    }
+                    "X" => record.x_player = value,
+                    "O" => record.o_player = value,
+                    "Date" => record.date = value,
+                    "Termination" => record.forfeit = value == FORFEIT_TERMINATION,
+                    "Result" => {
+                        stated_result = Some(
+                            GameResult::from_text(&value)
+                                .ok_or(RecordError::MalformedTag(line.to_string()))?,
+                        )
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            for token in line.split_whitespace() {
+                if token.ends_with('.') && token[..token.len() - 1].parse::<usize>().is_ok() {
+                    continue;
+                }
+                if let Some(result) = GameResult::from_text(token) {
+                    stated_result = Some(result);
+                    continue;
+                }
+                let pos = token
+                    .parse::<u8>()
+                    .ok()
+                    .and_then(Position::new)
+                    .ok_or(RecordError::MalformedMove(token.to_string()))?;
+                record.moves.push(pos);
+            }
+        }
+
+        let mut engine = GameEngine::with_rules(record.rules);
+        for (i, &pos) in record.moves.iter().enumerate() {
+            engine
+                .play_move(pos)
+                .map_err(|e| RecordError::IllegalMove(i, e))?;
+        }
+        record.result = GameResult::from_status(engine.status);
+        if record.forfeit {
+            // Play stopped before the end, and someone won by it
+            return match stated_result {
+                Some(result @ (GameResult::XWins | GameResult::OWins))
+                    if engine.status == GameStatus::Ongoing =>
+                {
+                    record.result = result;
+                    Ok(record)
+                }
+                _ => Err(RecordError::ResultMismatch),
+            };
+        }
+        if stated_result.is_some_and(|stated| stated != record.result) {
+            return Err(RecordError::ResultMismatch);
+        }
+        Ok(record)
+    }
+
+    /// Steps through the game, yielding the starting position and then the
+    /// position after each move.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {
+            rules: self.rules,
+            moves: &self.moves,
+            engine: None,
+            next: 0,
+        }
+    }
+}
+
+fn parse_tag(line: &str) -> Result<(&str, String), RecordError> {
+    let malformed = || RecordError::MalformedTag(line.to_string());
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .ok_or_else(malformed)?;
+    let (name, value) = inner.split_once(' ').ok_or_else(malformed)?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(malformed)?;
+    Ok((name, unescape(value).ok_or_else(malformed)?))
+}
+
+/// Escapes a tag value so it stays on one line and inside its quotes.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverses [`escape`]. Returns `None` for an unknown escape or an unescaped quote.
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                c @ ('\\' | '"') => unescaped.push(c),
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                _ => return None,
+            },
+            '"' => return None,
+            _ => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+/// Iterator over the positions of a recorded game. See [`GameRecord::replay`].
+pub struct Replay<'a> {
+    rules: RuleSet,
+    moves: &'a [Position],
+    engine: Option<GameEngine>,
+    next: usize,
+}
+
+impl Iterator for Replay<'_> {
+    type Item = GameEngine;
+
+    fn next(&mut self) -> Option<GameEngine> {
+        let Some(engine) = self.engine.as_mut() else {
+            let engine = GameEngine::with_rules(self.rules);
+            self.engine = Some(engine);
+            return Some(engine);
+        };
+        let &pos = self.moves.get(self.next)?;
+        self.next += 1;
+        // Records are validated when parsed, so every move is legal
+        engine.play_move(pos).ok()?;
+        Some(*engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_write_and_parse() {
+        let engine = played(&[4, 0, 2, 6, 3, 5, 1, 7, 8]);
+        let record = GameRecord::from_engine(&engine, "Alice", "Bob", "2026.10.17");
+        let text = record.to_text();
+        assert_eq!(
+            text,
+            concat!(
+                "[Variant \"classic\"]\n",
+                "[X \"Alice\"]\n",
+                "[O \"Bob\"]\n",
+                "[Date \"2026.10.17\"]\n",
+                "[Result \"1/2-1/2\"]\n",
+                "\n",
+                "1. 4 0 2. 2 6 3. 3 5 4. 1 7 5. 8 1/2-1/2\n",
+            )
+        );
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn test_replay_yields_each_position() {
+        let record = GameRecord::parse("1. 0 3 2. 1 4 3. 2 1-0").unwrap();
+        let positions: Vec<GameEngine> = record.replay().collect();
+        assert_eq!(positions.len(), 6);
+        assert_eq!(positions[0].board, [None; 9]);
+        assert_eq!(positions[1].board[0], Some(Player::X));
+        assert_eq!(
+            positions[5].status,
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 1, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_misere_records() {
+        let text = "[Variant \"misere\"]\n\n1. 0 3 2. 1 4 3. 2 0-1\n";
+        let record = GameRecord::parse(text).unwrap();
+        assert_eq!(record.result, GameResult::OWins);
+        let last = record.replay().last().unwrap();
+        assert_eq!(last.rules(), RuleSet::Misere);
+        assert_eq!(
+            GameRecord::from_engine(&last, "?", "?", "????.??.??"),
+            record
+        );
+        // Under standard rules X's line wins instead
+        assert_eq!(
+            GameRecord::parse(&text.replace("misere", "classic")),
+            Err(RecordError::ResultMismatch)
+        );
+    }
+
+    #[test]
+    fn test_forfeit_records() {
+        let record = GameRecord::from_engine(&played(&[4, 0]), "a", "b", "????.??.??")
+            .forfeited_by(Player::X);
+        let text = record.to_text();
+        assert!(text.contains("[Result \"0-1\"]\n[Termination \"forfeit\"]\n"));
+        assert!(text.ends_with("1. 4 0 0-1\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+
+        // A forfeit still needs a winner, and a game that ended on the board cannot
+        // be forfeited
+        let forfeit = "[Termination \"forfeit\"]\n\n";
+        assert_eq!(
+            GameRecord::parse(&format!("{}1. 4 0 *", forfeit)),
+            Err(RecordError::ResultMismatch)
+        );
+        assert_eq!(
+            GameRecord::parse(&format!("{}1. 0 3 2. 1 4 3. 2 0-1", forfeit)),
+            Err(RecordError::ResultMismatch)
+        );
+    }
+
+    #[test]
+    fn test_names_are_escaped() {
+        let engine = played(&[4]);
+        let record = GameRecord::from_engine(&engine, "A \"quoted\" \\ name", "two\nlines", "?");
+        let text = record.to_text();
+        assert!(text.contains("[X \"A \\\"quoted\\\" \\\\ name\"]\n"));
+        assert!(text.contains("[O \"two\\nlines\"]\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+
+        for tag in ["[X \"a\"b\"]", "[X \"a\\\"]", "[X \"a\\t\"]"] {
+            assert!(matches!(
+                GameRecord::parse(tag),
+                Err(RecordError::MalformedTag(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            GameRecord::parse("1. 0 0"),
+            Err(RecordError::IllegalMove(
+                1,
+                InvalidGameMoveError::SpaceOccupied
+            ))
+        );
+        assert_eq!(
+            GameRecord::parse("1. 0 9"),
+            Err(RecordError::MalformedMove("9".to_string()))
+        );
+        assert_eq!(
+            GameRecord::parse("1. 0 3 0-1"),
+            Err(RecordError::ResultMismatch)
+        );
+        assert_eq!(
+            GameRecord::parse("[Variant \"ultimate\"]\n"),
+            Err(RecordError::UnsupportedVariant("ultimate".to_string()))
+        );
+        assert!(matches!(
+            GameRecord::parse("[X Alice]\n"),
+            Err(RecordError::MalformedTag(_))
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+//! Game rules as a trait, for variants that only need a board, legal moves and a
+//! status.
+//!
+//! A [`Rules`] implementation decides what the board looks like, which moves are
+//! legal, what a move does to the board and when the game is over. [`game::Game`]
+//! runs any rule set with the usual turn order, undo and redo. Notakto, wild
+//! tic-tac-toe and Qubic are played this way.
+//!
+//! Not every variant goes through the trait. [`GameEngine`] keeps its own bitboards
+//! and move log for search speed and only borrows [`RuleSet`]'s move validation and
+//! status; m,n,k boards, gravity included, ultimate and quantum tic-tac-toe have
+//! engines of their own. What the search needs from all of them is
+//! [`SearchGame`](crate::mcts::SearchGame).
+//!
+//! [`RuleSet`] covers the classic board. Its rule sets play on the same board with
+//! the same moves; they differ in who a completed line counts for. Under misère rules
+//! the player who completes a line loses, so the game is about forcing your opponent
+//! to make three in a row.
+//!
+//! [`game::Game`]: crate::game::Game
+//! [`GameEngine`]: crate::GameEngine
+
+use crate::{
+    GameStatus, InvalidGameMoveError, Player, Position, bits_from_board, status_from_bits,
+};
+use std::fmt::Debug;
+
+/// The rules of a two-player game. Players alternate, starting with X.
+pub trait Rules {
+    /// Everything the rules need to know about a position.
+    type Board: Clone;
+    type Move: Copy + PartialEq + Debug;
+    /// What a win reports: usually the cells of the winning line.
+    type Line: Clone + PartialEq + Debug;
+
+    /// The board at the start of a game.
+    fn new_board(&self) -> Self::Board;
+
+    /// Checks that `player` may play `mv`. Only asked while the game is ongoing.
+    fn validate_move(
+        &self,
+        board: &Self::Board,
+        player: Player,
+        mv: Self::Move,
+    ) -> Result<(), InvalidGameMoveError>;
+
+    /// Every move `player` may play. Only asked while the game is ongoing.
+    fn legal_moves(&self, board: &Self::Board, player: Player) -> Vec<Self::Move>;
+
+    /// Plays a move that passed [`Rules::validate_move`].
+    fn apply_move(&self, board: &mut Self::Board, player: Player, mv: Self::Move);
+
+    /// Whether the game is over and who won, worked out from the board alone. Used
+    /// for boards that did not come from a move, such as the starting position.
+    fn status(&self, board: &Self::Board) -> GameStatus<Self::Line>;
+
+    /// The status right after `player` played `mv`. Rule sets that credit a win to
+    /// the mover rather than to whoever owns the line override this; others can use
+    /// it to check only what the move could have changed.
+    fn status_after(
+        &self,
+        board: &Self::Board,
+        _player: Player,
+        _mv: Self::Move,
+    ) -> GameStatus<Self::Line> {
+        self.status(board)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub enum RuleSet {
+    /// Three in a row wins.
+    #[default]
+    Standard,
+    /// Three in a row loses.
+    Misere,
+}
+
+impl RuleSet {
+    pub const ALL: [RuleSet; 2] = [RuleSet::Standard, RuleSet::Misere];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleSet::Standard => "standard",
+            RuleSet::Misere => "misere",
+        }
+    }
+
+    /// Parses a rule set name, ignoring case. `misère` is accepted too.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "standard" => Some(RuleSet::Standard),
+            "misere" | "misère" => Some(RuleSet::Misere),
+            _ => None,
+        }
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == RuleSet::Standard
+    }
+
+    /// Turns a status that names the owner of a completed line into one that names
+    /// the winner. The line itself is kept either way.
+    pub(crate) fn adjudicate(self, status: GameStatus) -> GameStatus {
+        match (self, status) {
+            (RuleSet::Misere, GameStatus::Win { player, line }) => GameStatus::Win {
+                player: player.next(),
+                line,
+            },
+            _ => status,
+        }
+    }
+}
+
+impl Rules for RuleSet {
+    type Board = [Option<Player>; 9];
+    type Move = Position;
+    type Line = [usize; 3];
+
+    fn new_board(&self) -> Self::Board {
+        [None; 9]
+    }
+
+    fn validate_move(
+        &self,
+        board: &Self::Board,
+        _player: Player,
+        pos: Position,
+    ) -> Result<(), InvalidGameMoveError> {
+        match board[pos.to_index()] {
+            Some(_) => Err(InvalidGameMoveError::SpaceOccupied),
+            None => Ok(()),
+        }
+    }
+
+    fn legal_moves(&self, board: &Self::Board, _player: Player) -> Vec<Position> {
+        (0..9)
+            .map(Position)
+            .filter(|pos| board[pos.to_index()].is_none())
+            .collect()
+    }
+
+    fn apply_move(&self, board: &mut Self::Board, player: Player, pos: Position) {
+        board[pos.to_index()] = Some(player);
+    }
+
+    fn status(&self, board: &Self::Board) -> GameStatus {
+        self.adjudicate(status_from_bits(bits_from_board(board)))
+    }
+}
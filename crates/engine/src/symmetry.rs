@@ -0,0 +1,182 @@
+//! The eight symmetries of the square and canonical forms of classic boards.
+//!
+//! Rotating or reflecting a board does not change its game-theoretic value, so
+//! analysis only needs one representative per family of equivalent boards: the
+//! canonical form. A [`Transform`] records how a board was mapped to its canonical
+//! form, and can map moves found there back to the original orientation.
+
+use crate::{GameEngine, Player, Position, zobrist};
+
+/// One of the eight rotations and reflections of a 3x3 board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    /// A quarter turn counter-clockwise.
+    Rotate270,
+    /// Swaps the left and right columns.
+    FlipHorizontal,
+    /// Swaps the top and bottom rows.
+    FlipVertical,
+    /// Reflects in the diagonal through cells 0, 4 and 8.
+    FlipDiagonal,
+    /// Reflects in the diagonal through cells 2, 4 and 6.
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::FlipDiagonal,
+        Transform::FlipAntiDiagonal,
+    ];
+
+    /// Where the cell at `pos` ends up after the transform.
+    pub fn apply(&self, pos: Position) -> Position {
+        let (row, col) = (pos.0 / 3, pos.0 % 3);
+        let (row, col) = match self {
+            Transform::Identity => (row, col),
+            Transform::Rotate90 => (col, 2 - row),
+            Transform::Rotate180 => (2 - row, 2 - col),
+            Transform::Rotate270 => (2 - col, row),
+            Transform::FlipHorizontal => (row, 2 - col),
+            Transform::FlipVertical => (2 - row, col),
+            Transform::FlipDiagonal => (col, row),
+            Transform::FlipAntiDiagonal => (2 - col, 2 - row),
+        };
+        Position(row * 3 + col)
+    }
+
+    /// Maps a cell of the transformed board back to the original orientation.
+    pub fn revert(&self, pos: Position) -> Position {
+        self.inverse().apply(pos)
+    }
+
+    /// The transform that undoes this one.
+    pub fn inverse(&self) -> Transform {
+        match self {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => *other,
+        }
+    }
+
+    pub fn apply_board(&self, board: &[Option<Player>; 9]) -> [Option<Player>; 9] {
+        let mut transformed = [None; 9];
+        for (i, &cell) in board.iter().enumerate() {
+            transformed[self.apply(Position(i as u8)).to_index()] = cell;
+        }
+        transformed
+    }
+}
+
+/// Orders boards by reading the cells as base-3 digits; the smallest is canonical.
+pub(crate) fn board_key(board: &[Option<Player>; 9]) -> u32 {
+    board.iter().fold(0, |key, cell| {
+        let digit = match cell {
+            None => 0,
+            Some(Player::X) => 1,
+            Some(Player::O) => 2,
+        };
+        key * 3 + digit
+    })
+}
+
+impl GameEngine {
+    /// Returns the canonical form of the board and the transform that produces it, so
+    /// that `transform.apply_board(&self.board)` is the canonical board.
+    ///
+    /// Equivalent boards share a canonical form. Moves chosen on the canonical board
+    /// map back to this one with [`Transform::revert`].
+    pub fn canonical(&self) -> ([Option<Player>; 9], Transform) {
+        Transform::ALL
+            .iter()
+            .map(|&transform| (transform.apply_board(&self.board), transform))
+            .min_by_key(|(board, _)| board_key(board))
+            .expect("there are eight transforms")
+    }
+
+    /// Zobrist hash of the canonical board: the same for all equivalent boards, so it
+    /// can key transposition tables and deduplicate positions.
+    pub fn canonical_hash(&self) -> u64 {
+        zobrist::hash_board(&self.canonical().0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameStatus;
+    use std::collections::HashSet;
+
+    fn play(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_transforms_permute_cells() {
+        assert_eq!(Transform::Rotate90.apply(Position(0)), Position(2));
+        assert_eq!(Transform::FlipDiagonal.apply(Position(1)), Position(3));
+        for transform in Transform::ALL {
+            let mut seen: Vec<usize> = (0..9)
+                .map(|i| transform.apply(Position(i)).to_index())
+                .collect();
+            seen.sort_unstable();
+            assert_eq!(seen, (0..9).collect::<Vec<_>>());
+            for i in 0..9 {
+                assert_eq!(transform.revert(transform.apply(Position(i))), Position(i));
+            }
+            // The center never moves
+            assert_eq!(transform.apply(Position(4)), Position(4));
+        }
+    }
+
+    #[test]
+    fn test_equivalent_boards_share_canonical_form() {
+        let corner = play(&[0, 4]);
+        for opening in [2, 6, 8] {
+            let other = play(&[opening, 4]);
+            assert_eq!(other.canonical().0, corner.canonical().0);
+            assert_eq!(other.canonical_hash(), corner.canonical_hash());
+        }
+        assert_ne!(play(&[1, 4]).canonical_hash(), corner.canonical_hash());
+    }
+
+    #[test]
+    fn test_revert_maps_moves_back() {
+        // X's piece on the canonical board maps back to the corner X really took.
+        let engine = play(&[8, 4]);
+        let (board, transform) = engine.canonical();
+        let corner = (0..9)
+            .map(Position)
+            .find(|&p| board[p.to_index()] == Some(Player::X))
+            .unwrap();
+        assert_eq!(transform.revert(corner), Position(8));
+    }
+
+    #[test]
+    fn test_765_positions_up_to_symmetry() {
+        fn visit(engine: &GameEngine, seen: &mut HashSet<u64>) {
+            if !seen.insert(engine.canonical_hash()) || engine.status != GameStatus::Ongoing {
+                return;
+            }
+            for pos in engine.legal_moves() {
+                visit(&engine.with_move(pos).unwrap(), seen);
+            }
+        }
+        let mut seen = HashSet::new();
+        visit(&GameEngine::new(), &mut seen);
+        assert_eq!(seen.len(), 765);
+    }
+}
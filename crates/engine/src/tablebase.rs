@@ -0,0 +1,369 @@
+//! Complete solution of classic tic-tac-toe under standard rules.
+//!
+//! [`Tablebase::solve`] visits every position reachable from [`GameEngine::new`] and
+//! records who wins with perfect play and how many moves remain until the game ends.
+//! Lookups index straight into the table by the board's base-3 code, so answers take
+//! constant time with no search.
+//!
+//! The binary form is a 4-byte magic followed by one byte per base-3 board code
+//! (3^9 = 19,683 bytes): `0xFF` for boards that cannot occur in a game, otherwise the
+//! value in the low two bits and the distance in the next four.
+//!
+//! Front ends solve the game once at build time: their build script calls
+//! [`write_for_build`] and the crate loads the result with [`embedded_tablebase!`].
+//!
+//! [`embedded_tablebase!`]: crate::embedded_tablebase
+
+use crate::symmetry::board_key;
+use crate::{GameEngine, GameStatus, Player, Position};
+use std::path::Path;
+use std::sync::OnceLock;
+
+const MAGIC: &[u8; 4] = b"TTB1";
+const BOARDS: usize = 19_683;
+const UNREACHABLE: u8 = 0xFF;
+
+/// Who wins a position with perfect play from both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub enum Value {
+    XWins,
+    OWins,
+    Draw,
+}
+
+/// What the tablebase knows about one position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct Entry {
+    pub value: Value,
+    /// Moves left in the game when the winner wins as fast as possible and the loser
+    /// holds out as long as possible. Zero for finished games.
+    pub distance: u8,
+}
+
+impl Entry {
+    fn to_byte(self) -> u8 {
+        let value = match self.value {
+            Value::Draw => 0,
+            Value::XWins => 1,
+            Value::OWins => 2,
+        };
+        value | self.distance << 2
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        let value = match byte & 0b11 {
+            0 => Value::Draw,
+            1 => Value::XWins,
+            2 => Value::OWins,
+            _ => return None,
+        };
+        let distance = byte >> 2;
+        (distance <= 9).then_some(Entry { value, distance })
+    }
+
+    /// How good this entry is for `player`: wins beat draws beat losses, faster wins
+    /// and slower losses are better.
+    fn score_for(&self, player: Player) -> i32 {
+        let distance = self.distance as i32;
+        match (self.value, player) {
+            (Value::Draw, _) => 0,
+            (Value::XWins, Player::X) | (Value::OWins, Player::O) => 100 - distance,
+            _ => distance - 100,
+        }
+    }
+}
+
+/// Represents errors that can occur when loading a serialized tablebase.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TablebaseError {
+    /// The data does not start with the tablebase magic bytes.
+    BadMagic,
+    /// The data is not the size of a full table.
+    WrongLength,
+    /// An entry is neither unreachable nor a valid value and distance.
+    BadEntry(usize),
+}
+
+pub struct Tablebase {
+    /// One byte per base-3 board code.
+    entries: Vec<u8>,
+}
+
+impl Tablebase {
+    /// Solves the game by visiting every reachable position.
+    pub fn solve() -> Self {
+        let mut table = Self {
+            entries: vec![UNREACHABLE; BOARDS],
+        };
+        table.solve_from(&GameEngine::new());
+        table
+    }
+
+    fn solve_from(&mut self, engine: &GameEngine) -> Entry {
+        let key = board_key(&engine.board) as usize;
+        if let Some(entry) = Entry::from_byte(self.entries[key]) {
+            return entry;
+        }
+
+        let entry = match engine.status {
+            GameStatus::Win { player, .. } => Entry {
+                value: match player {
+                    Player::X => Value::XWins,
+                    Player::O => Value::OWins,
+                },
+                distance: 0,
+            },
+            GameStatus::Draw => Entry {
+                value: Value::Draw,
+                distance: 0,
+            },
+            GameStatus::Ongoing => {
+                let player = engine.current_player;
+                let best = children(engine)
+                    .map(|(_, child)| self.solve_from(&child))
+                    .max_by_key(|entry| entry.score_for(player))
+                    .expect("an ongoing game has a legal move");
+                Entry {
+                    value: best.value,
+                    distance: best.distance + 1,
+                }
+            }
+        };
+        self.entries[key] = entry.to_byte();
+        entry
+    }
+
+    /// Number of reachable positions in the table.
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|&&b| b != UNREACHABLE).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Looks up a position, or returns `None` if it cannot occur in a game or is
+    /// played under other rules.
+    pub fn get(&self, engine: &GameEngine) -> Option<Entry> {
+        if !engine.rules().is_standard() {
+            return None;
+        }
+        Entry::from_byte(self.entries[board_key(&engine.board) as usize])
+    }
+
+    /// A perfect move for the side to move: the fastest win, else a draw, else the
+    /// slowest loss. Ties go to the lowest board index. `None` if the game is over or
+    /// the position is not in the table.
+    pub fn best_move(&self, engine: &GameEngine) -> Option<Position> {
+        let player = engine.current_player;
+        let mut best: Option<(Position, i32)> = None;
+        for (pos, entry) in self.move_values(engine)? {
+            let score = entry.score_for(player);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((pos, score));
+            }
+        }
+        best.map(|(pos, _)| pos)
+    }
+
+    /// Every legal move with the entry of the position it leads to, in board order.
+    /// The entry's distance counts the moves left after that move. `None` if the
+    /// position is not in the table.
+    pub fn move_values(&self, engine: &GameEngine) -> Option<Vec<(Position, Entry)>> {
+        children(engine)
+            .map(|(pos, child)| Some((pos, self.get(&child)?)))
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&self.entries);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TablebaseError> {
+        let entries = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(TablebaseError::BadMagic)?;
+        if entries.len() != BOARDS {
+            return Err(TablebaseError::WrongLength);
+        }
+        if let Some(i) = entries
+            .iter()
+            .position(|&b| b != UNREACHABLE && Entry::from_byte(b).is_none())
+        {
+            return Err(TablebaseError::BadEntry(i));
+        }
+        Ok(Self {
+            entries: entries.to_vec(),
+        })
+    }
+}
+
+/// Solves the game and writes it to `tablebase.bin` in `OUT_DIR`. Call from a build
+/// script, then load the table with [`embedded_tablebase!`](crate::embedded_tablebase).
+pub fn write_for_build() {
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let path = Path::new(&out_dir).join("tablebase.bin");
+    std::fs::write(path, Tablebase::solve().to_bytes()).expect("tablebase is writable");
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// The table in `bytes`, decoded on first use and kept for the rest of the program.
+/// Every caller shares the first table decoded. Use
+/// [`embedded_tablebase!`](crate::embedded_tablebase) rather than calling this directly.
+pub fn embedded(bytes: &'static [u8]) -> &'static Tablebase {
+    static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+    TABLEBASE.get_or_init(|| {
+        Tablebase::from_bytes(bytes).expect("write_for_build writes a valid tablebase")
+    })
+}
+
+/// The tablebase the calling crate's build script wrote with [`write_for_build`],
+/// as a `&'static Tablebase`.
+#[macro_export]
+macro_rules! embedded_tablebase {
+    () => {
+        $crate::tablebase::embedded(include_bytes!(concat!(env!("OUT_DIR"), "/tablebase.bin")))
+    };
+}
+
+/// Legal moves from `engine` with the positions they lead to.
+fn children(engine: &GameEngine) -> impl Iterator<Item = (Position, GameEngine)> + '_ {
+    engine.legal_moves().map(|pos| {
+        let child = engine.with_move(pos).expect("legal moves can be played");
+        (pos, child)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai;
+
+    fn play(moves: &[u8]) -> GameEngine {
+        let mut engine = GameEngine::new();
+        for &m in moves {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        engine
+    }
+
+    /// Every reachable position, including the empty board.
+    fn all_positions(engine: &GameEngine, out: &mut Vec<GameEngine>, seen: &mut [bool]) {
+        let key = board_key(&engine.board) as usize;
+        if std::mem::replace(&mut seen[key], true) {
+            return;
+        }
+        out.push(*engine);
+        for (_, child) in children(engine) {
+            all_positions(&child, out, seen);
+        }
+    }
+
+    #[test]
+    fn test_solves_every_reachable_position() {
+        let table = Tablebase::solve();
+        assert_eq!(table.len(), 5478);
+        assert_eq!(
+            table.get(&GameEngine::new()),
+            Some(Entry {
+                value: Value::Draw,
+                distance: 9
+            })
+        );
+        // X to play at 2 wins at once
+        assert_eq!(
+            table.get(&play(&[0, 3, 1, 4])),
+            Some(Entry {
+                value: Value::XWins,
+                distance: 1
+            })
+        );
+        assert_eq!(table.best_move(&play(&[0, 3, 1, 4])), Position::new(2));
+        // O on an edge after X's corner opening loses
+        assert_eq!(table.get(&play(&[0, 1])).unwrap().value, Value::XWins);
+        // A lone O cannot occur: X always moves first
+        let mut impossible = GameEngine::new();
+        impossible.board[0] = Some(Player::O);
+        assert_eq!(table.get(&impossible), None);
+    }
+
+    #[test]
+    fn test_move_values() {
+        let table = Tablebase::solve();
+        // X threatens the top row: O must block at 2 or lose next move
+        let values = table.move_values(&play(&[0, 4, 1])).unwrap();
+        assert_eq!(values.len(), 6);
+        for (pos, entry) in values {
+            let expected = match pos.to_index() {
+                2 => Value::Draw,
+                _ => Value::XWins,
+            };
+            assert_eq!(entry.value, expected, "{:?}", pos);
+            if expected == Value::XWins {
+                assert_eq!(entry.distance, 1);
+            }
+        }
+        assert_eq!(table.move_values(&play(&[0, 3, 1, 4, 2])), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_round_trip_bytes() {
+        let table = Tablebase::solve();
+        let bytes = table.to_bytes();
+        assert_eq!(bytes.len(), 4 + BOARDS);
+        let loaded = Tablebase::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.entries, table.entries);
+
+        assert_eq!(
+            Tablebase::from_bytes(b"nope").err(),
+            Some(TablebaseError::BadMagic)
+        );
+        assert_eq!(
+            Tablebase::from_bytes(&bytes[..100]).err(),
+            Some(TablebaseError::WrongLength)
+        );
+        let mut corrupt = bytes.clone();
+        corrupt[4] = 0b11;
+        assert_eq!(
+            Tablebase::from_bytes(&corrupt).err(),
+            Some(TablebaseError::BadEntry(0))
+        );
+    }
+
+    #[test]
+    fn test_search_agrees_with_tablebase() {
+        let table = Tablebase::solve();
+        let mut positions = Vec::new();
+        all_positions(&GameEngine::new(), &mut positions, &mut vec![false; BOARDS]);
+        assert_eq!(positions.len(), 5478);
+
+        for engine in positions {
+            let entry = table.get(&engine).unwrap();
+            let winner = match entry.value {
+                Value::XWins => Some(Player::X),
+                Value::OWins => Some(Player::O),
+                Value::Draw => None,
+            };
+            assert_eq!(ai::solve(&engine), winner, "{}", engine.to_notation());
+
+            // The search's move must keep the value and take the optimal distance
+            if let Some(pos) = ai::best_move(&engine) {
+                let mut child = engine;
+                child.play_move(pos).unwrap();
+                let after = table.get(&child).unwrap();
+                assert_eq!(after.value, entry.value, "{}", engine.to_notation());
+                assert_eq!(
+                    after.distance + 1,
+                    entry.distance,
+                    "{}",
+                    engine.to_notation()
+                );
+            }
+        }
+    }
+}
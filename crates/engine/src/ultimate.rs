@@ -0,0 +1,184 @@
+//! Ultimate (meta) tic-tac-toe: nine classic boards arranged in a 3x3 grid.
+//!
+//! The cell you play in picks the board your opponent must play in next. Winning a
+//! small board claims the matching cell of the meta-board, and three claimed cells
+//! in a row win the game. If you are sent to a board that is already won or full,
+//! you may play on any board that is still open.
+
+use crate::{GameEngine, GameStatus, InvalidGameMoveError, Player, Position};
+
+/// A cell on the ultimate board: which small board, then which cell of it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UltimatePosition {
+    pub board: Position,
+    pub cell: Position,
+}
+
+impl UltimatePosition {
+    pub fn new(board: u8, cell: u8) -> Option<Self> {
+        Some(Self {
+            board: Position::new(board)?,
+            cell: Position::new(cell)?,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[cfg_attr(feature = "wasm", serde(rename_all = "camelCase"))]
+pub struct UltimateEngine {
+    pub boards: [GameEngine; 9],
+    pub current_player: Player,
+    /// The board the current player must play in, or `None` if any open board is allowed.
+    pub active_board: Option<Position>,
+    pub status: GameStatus,
+}
+
+impl Default for UltimateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UltimateEngine {
+    pub fn new() -> Self {
+        Self {
+            boards: [GameEngine::new(); 9],
+            current_player: Player::X,
+            active_board: None,
+            status: GameStatus::Ongoing,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn play_move(&mut self, pos: UltimatePosition) -> Result<(), InvalidGameMoveError> {
+        self.validate_move(pos)?;
+
+        // Each small board is a classic engine; make sure it places our symbol
+        let board = &mut self.boards[pos.board.to_index()];
+        board.current_player = self.current_player;
+        board.play_move(pos.cell)?;
+
+        // Send the opponent to the matching board, unless it is already finished
+        let next = &self.boards[pos.cell.to_index()];
+        self.active_board = (next.status == GameStatus::Ongoing).then_some(pos.cell);
+
+        self.status = self.calculate_status();
+        self.current_player = self.current_player.next();
+        Ok(())
+    }
+
+    pub fn validate_move(&self, pos: UltimatePosition) -> Result<(), InvalidGameMoveError> {
+        if self.status != GameStatus::Ongoing {
+            return Err(InvalidGameMoveError::GameAlreadyWon);
+        }
+        if self.active_board.is_some_and(|active| active != pos.board) {
+            return Err(InvalidGameMoveError::WrongBoard);
+        }
+        self.boards[pos.board.to_index()].validate_move(pos.cell)
+    }
+
+    /// The meta-board: each cell is claimed by whoever won the matching small board.
+    pub fn meta_board(&self) -> [Option<Player>; 9] {
+        self.boards.map(|board| match board.status {
+            GameStatus::Win { player, .. } => Some(player),
+            _ => None,
+        })
+    }
+
+    /// Status of the meta-board. The winning line refers to small-board indices.
+    ///
+    /// The game is drawn once every small board is finished without a winning line.
+    pub fn calculate_status(&self) -> GameStatus {
+        let mut meta = GameEngine::new();
+        meta.board = self.meta_board();
+        match meta.calculate_status() {
+            GameStatus::Win { player, line } => GameStatus::Win { player, line },
+            _ if self.boards.iter().all(|b| b.status != GameStatus::Ongoing) => GameStatus::Draw,
+            _ => GameStatus::Ongoing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(engine: &mut UltimateEngine, moves: &[(u8, u8)]) {
+        for &(board, cell) in moves {
+            engine
+                .play_move(UltimatePosition::new(board, cell).unwrap())
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_move_sends_opponent_to_board() {
+        let mut engine = UltimateEngine::new();
+        play(&mut engine, &[(4, 2)]);
+        assert_eq!(engine.active_board, Position::new(2));
+        assert_eq!(engine.boards[4].board[2], Some(Player::X));
+        assert_eq!(engine.current_player, Player::O);
+        assert_eq!(
+            engine.play_move(UltimatePosition::new(5, 0).unwrap()),
+            Err(InvalidGameMoveError::WrongBoard)
+        );
+    }
+
+    #[test]
+    fn test_claiming_a_board() {
+        let mut engine = UltimateEngine::new();
+        // X takes the top row of board 0; O's replies keep sending X back there.
+        play(&mut engine, &[(0, 1), (1, 0), (0, 2), (2, 0), (0, 0)]);
+        assert_eq!(
+            engine.boards[0].status,
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 1, 2]
+            }
+        );
+        assert_eq!(engine.meta_board()[0], Some(Player::X));
+    }
+
+    #[test]
+    fn test_sent_to_finished_board_plays_anywhere() {
+        let mut engine = UltimateEngine::new();
+        play(&mut engine, &[(0, 1), (1, 0), (0, 2), (2, 0), (0, 0)]);
+        // O is sent to board 0, which X has already won.
+        assert_eq!(engine.active_board, None);
+        assert_eq!(
+            engine.play_move(UltimatePosition::new(0, 8).unwrap()),
+            Err(InvalidGameMoveError::GameAlreadyWon)
+        );
+        play(&mut engine, &[(8, 8)]);
+        assert_eq!(engine.active_board, Position::new(8));
+    }
+
+    #[test]
+    fn test_meta_win() {
+        let mut engine = UltimateEngine::new();
+        // Give X the top row of boards 0 and 1, and two thirds of board 2's.
+        for (board, cells) in [(0, &[0, 1, 2][..]), (1, &[0, 1, 2]), (2, &[0, 1])] {
+            for &cell in cells {
+                let small = &mut engine.boards[board];
+                small.current_player = Player::X;
+                small.play_move(Position::new(cell).unwrap()).unwrap();
+            }
+        }
+        play(&mut engine, &[(2, 2)]);
+        assert_eq!(
+            engine.status,
+            GameStatus::Win {
+                player: Player::X,
+                line: [0, 1, 2]
+            }
+        );
+        assert_eq!(
+            engine.play_move(UltimatePosition::new(4, 4).unwrap()),
+            Err(InvalidGameMoveError::GameAlreadyWon)
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Wild tic-tac-toe: on each turn the mover chooses whether to place an X or an O.
+//!
+//! Whoever completes a line of either symbol wins, so the players are the first and
+//! second to move rather than "X" and "O". [`Player::X`] moves first as usual, but
+//! its pieces are whatever symbols it chose.
+
+use crate::game::Game;
+use crate::rules::Rules;
+use crate::{GameStatus, InvalidGameMoveError, Player, Position, WINNING_LINES};
+
+/// A wild move: where to play and which symbol to place there.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+pub struct WildMove {
+    pub pos: Position,
+    pub symbol: Player,
+}
+
+impl WildMove {
+    pub fn new(pos: u8, symbol: Player) -> Option<Self> {
+        Some(Self {
+            pos: Position::new(pos)?,
+            symbol,
+        })
+    }
+}
+
+/// The rules of wild tic-tac-toe. The board holds symbols, not owners.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Wild;
+
+pub type WildGame = Game<Wild>;
+
+/// A line of three equal symbols on `board`, if there is one.
+fn find_line(board: &[Option<Player>; 9]) -> Option<(Player, [usize; 3])> {
+    WINNING_LINES.iter().find_map(|&(_, [a, b, c])| {
+        let symbol = board[a]?;
+        (board[b] == Some(symbol) && board[c] == Some(symbol)).then_some((symbol, [a, b, c]))
+    })
+}
+
+impl Rules for Wild {
+    type Board = [Option<Player>; 9];
+    type Move = WildMove;
+    type Line = [usize; 3];
+
+    fn new_board(&self) -> Self::Board {
+        [None; 9]
+    }
+
+    fn validate_move(
+        &self,
+        board: &Self::Board,
+        _player: Player,
+        mv: WildMove,
+    ) -> Result<(), InvalidGameMoveError> {
+        match board[mv.pos.to_index()] {
+            Some(_) => Err(InvalidGameMoveError::SpaceOccupied),
+            None => Ok(()),
+        }
+    }
+
+    fn legal_moves(&self, board: &Self::Board, _player: Player) -> Vec<WildMove> {
+        (0..9)
+            .map(Position)
+            .filter(|pos| board[pos.to_index()].is_none())
+            .flat_map(|pos| [Player::X, Player::O].map(|symbol| WildMove { pos, symbol }))
+            .collect()
+    }
+
+    fn apply_move(&self, board: &mut Self::Board, _player: Player, mv: WildMove) {
+        board[mv.pos.to_index()] = Some(mv.symbol);
+    }
+
+    /// From the board alone there is no telling who completed a line, so a finished
+    /// line is credited to the player named after its symbol.
+    fn status(&self, board: &Self::Board) -> GameStatus {
+        match find_line(board) {
+            Some((symbol, line)) => GameStatus::Win {
+                player: symbol,
+                line,
+            },
+            None if board.iter().all(Option::is_some) => GameStatus::Draw,
+            None => GameStatus::Ongoing,
+        }
+    }
+
+    /// The mover wins by completing a line, whichever symbol it is made of.
+    fn status_after(&self, board: &Self::Board, player: Player, _mv: WildMove) -> GameStatus {
+        match self.status(board) {
+            GameStatus::Win { line, .. } => GameStatus::Win { player, line },
+            status => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut WildGame, moves: &[(u8, Player)]) {
+        for &(pos, symbol) in moves {
+            game.play_move(WildMove::new(pos, symbol).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_mover_wins_with_either_symbol() {
+        // The second player completes a row of X's
+        let mut game = WildGame::default();
+        play(&mut game, &[(0, Player::X), (1, Player::X), (4, Player::O)]);
+        assert_eq!(*game.status(), GameStatus::Ongoing);
+        play(&mut game, &[(2, Player::X)]);
+        assert_eq!(
+            *game.status(),
+            GameStatus::Win {
+                player: Player::O,
+                line: [0, 1, 2]
+            }
+        );
+        assert_eq!(game.board()[2], Some(Player::X));
+    }
+
+    #[test]
+    fn test_moves_carry_a_symbol() {
+        let mut game = WildGame::default();
+        assert_eq!(game.legal_moves().len(), 18);
+        play(&mut game, &[(4, Player::O)]);
+        assert_eq!(game.legal_moves().len(), 16);
+        assert_eq!(
+            game.play_move(WildMove::new(4, Player::X).unwrap()),
+            Err(InvalidGameMoveError::SpaceOccupied)
+        );
+        assert_eq!(game.current_player(), Player::O);
+    }
+}
@@ -0,0 +1,89 @@
+//! Zobrist hashing of classic boards.
+//!
+//! Every (player, cell) pair has a random 64-bit key and a board hashes to the XOR of
+//! the keys of its pieces, so placing or removing a piece updates the hash with a
+//! single XOR. `GameEngine` keeps its hash current in `play_move`, `undo` and `redo`.
+//!
+//! The side to move is not hashed: in a real game it follows from the piece counts.
+
+use crate::rng::Rng;
+use crate::{Player, Position};
+
+/// Fixed seed so hashes are stable across runs and builds.
+const SEED: u64 = 0x7469_635f_7461_635f;
+
+const KEYS: [[u64; 9]; 2] = generate_keys();
+
+const fn generate_keys() -> [[u64; 9]; 2] {
+    let mut rng = Rng::new(SEED);
+    let mut keys = [[0; 9]; 2];
+    let mut player = 0;
+    while player < 2 {
+        let mut cell = 0;
+        while cell < 9 {
+            keys[player][cell] = rng.next_u64();
+            cell += 1;
+        }
+        player += 1;
+    }
+    keys
+}
+
+/// The key XORed into the hash when `player` takes `pos`.
+pub fn key(player: Player, pos: Position) -> u64 {
+    let player = match player {
+        Player::X => 0,
+        Player::O => 1,
+    };
+    KEYS[player][pos.to_index()]
+}
+
+/// Hashes a board from scratch.
+pub fn hash_board(board: &[Option<Player>; 9]) -> u64 {
+    board
+        .iter()
+        .enumerate()
+        .filter_map(|(i, cell)| Some(key((*cell)?, Position(i as u8))))
+        .fold(0, |hash, key| hash ^ key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEngine;
+
+    #[test]
+    fn test_keys_are_distinct() {
+        let mut keys: Vec<u64> = KEYS.iter().flatten().copied().collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), 18);
+    }
+
+    #[test]
+    fn test_incremental_hash_matches_full_hash() {
+        let mut engine = GameEngine::new();
+        assert_eq!(engine.zobrist_hash(), 0);
+        for &m in &[4, 0, 8, 2] {
+            engine.play_move(Position::new(m).unwrap()).unwrap();
+            assert_eq!(engine.zobrist_hash(), hash_board(&engine.board));
+        }
+        let after = engine.zobrist_hash();
+
+        engine.undo();
+        engine.undo();
+        assert_eq!(engine.zobrist_hash(), hash_board(&engine.board));
+        engine.redo();
+        engine.redo();
+        assert_eq!(engine.zobrist_hash(), after);
+
+        // Transpositions hash the same
+        let mut other = GameEngine::new();
+        for &m in &[8, 2, 4, 0] {
+            other.play_move(Position::new(m).unwrap()).unwrap();
+        }
+        assert_eq!(other.zobrist_hash(), after);
+        let parsed = GameEngine::from_notation(&other.to_notation()).unwrap();
+        assert_eq!(parsed.zobrist_hash(), after);
+    }
+}
@@ -0,0 +1,6 @@
+// Solve the game at build time so the analysis overlay never has to search while a
+// frame is being drawn.
+
+fn main() {
+    rust_tac_toe_engine::tablebase::write_for_build();
+}
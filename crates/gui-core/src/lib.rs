@@ -1,21 +1,136 @@
 //! eframe/egui Tic-Tac-Toe: Refined UI with stable layout and dark aesthetic.
 
-use eframe::{egui, App};
+use eframe::{App, egui};
+use rust_tac_toe_engine::agent::{Agent, HeuristicPlayer, HumanPlayer};
+use rust_tac_toe_engine::ai::{AiPlayer, Difficulty};
+use rust_tac_toe_engine::analysis::Hints;
+use rust_tac_toe_engine::mcts::{Budget, Mcts};
+use rust_tac_toe_engine::mnk::MnkEngine;
+use rust_tac_toe_engine::notakto::{self, Notakto, NotaktoGame, NotaktoMove};
+use rust_tac_toe_engine::quantum::{Mark, QuantumEngine, QuantumMove, QuantumStatus};
+use rust_tac_toe_engine::qubic::{self, CubePosition, QubicGame};
+use rust_tac_toe_engine::rules::RuleSet;
+use rust_tac_toe_engine::tablebase::{Entry, Tablebase, Value};
+use rust_tac_toe_engine::ultimate::{UltimateEngine, UltimatePosition};
+use rust_tac_toe_engine::wild::{WildGame, WildMove};
 use rust_tac_toe_engine::{GameEngine, GameStatus, Player, Position};
 
 // Style Constants
 const BOARD_SIZE: f32 = 360.0;
 const CELL_GAP: f32 = 12.0;
 const CORNER_RADIUS: f32 = 10.0;
+const SUB_CELL_GAP: f32 = 4.0;
+/// How long a Connect Four piece takes to fall from the top row to the bottom.
+const DROP_SECONDS: f64 = 0.35;
+/// As many Notakto boards as fit side by side in the window.
+const MAX_NOTAKTO_BOARDS: usize = 4;
+/// Gap between the cells of a Qubic layer, which are small enough already.
+const QUBIC_CELL_GAP: f32 = 2.0;
+/// Outline for hinted fork moves; wins and blocks use the players' colours.
+const FORK_HINT_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 200, 80);
+/// Playouts per move for the MCTS seat, counted rather than timed so that it also
+/// runs where there is no clock.
+const MCTS_PLAYOUTS: u32 = 2_000;
+
+/// The solved game, generated by build.rs
+fn tablebase() -> &'static Tablebase {
+    rust_tac_toe_engine::embedded_tablebase!()
+}
+
+/// Which game is on the board.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Classic,
+    Ultimate,
+    Wild,
+    Notakto,
+    Gravity,
+    Quantum,
+    Qubic,
+}
+
+impl Mode {
+    fn label(&self) -> &'static str {
+        match self {
+            Mode::Classic => "CLASSIC",
+            Mode::Ultimate => "ULTIMATE",
+            Mode::Wild => "WILD",
+            Mode::Notakto => "NOTAKTO",
+            Mode::Gravity => "CONNECT FOUR",
+            Mode::Quantum => "QUANTUM",
+            Mode::Qubic => "QUBIC",
+        }
+    }
+}
+
+/// A player that can be seated on either side of the classic game.
+struct SeatChoice {
+    name: String,
+    /// Builds the agent from a seed, so each new computer player plays differently.
+    make: Box<dyn Fn(u64) -> Box<dyn Agent>>,
+}
+
+/// A Connect Four piece on its way down: the cell it lands in and when it was dropped.
+#[derive(Clone, Copy)]
+struct Falling {
+    index: usize,
+    started: f64,
+}
 
 pub struct TicTacToeApp {
+    mode: Mode,
     engine: GameEngine,
+    ultimate: UltimateEngine,
+    wild: WildGame,
+    /// The symbol a click places in the wild game.
+    wild_symbol: Player,
+    notakto: NotaktoGame,
+    /// Whether the computer plays second in Notakto, always perfectly.
+    notakto_computer: bool,
+    gravity: MnkEngine,
+    /// The piece being animated, if any. It is already on the board.
+    falling: Option<Falling>,
+    quantum: QuantumEngine,
+    /// The first square picked for a quantum mark, waiting for the second.
+    quantum_first: Option<Position>,
+    qubic: QubicGame,
+    /// Whether the computer plays O in Qubic.
+    qubic_computer: bool,
+    /// The players that can be seated, starting with a human.
+    seat_choices: Vec<SeatChoice>,
+    /// Which of `seat_choices` is seated as X and O.
+    seats: [usize; 2],
+    agents: [Box<dyn Agent>; 2],
+    /// Highlight winning, blocking and fork moves for the side to move.
+    show_hints: bool,
+    /// Rules for the classic game; changing them starts a new game.
+    rules: RuleSet,
+    /// Label each empty cell with the outcome of playing there.
+    analysis: bool,
 }
 
 impl Default for TicTacToeApp {
     fn default() -> Self {
         Self {
+            mode: Mode::Classic,
             engine: GameEngine::new(),
+            ultimate: UltimateEngine::new(),
+            wild: WildGame::default(),
+            wild_symbol: Player::X,
+            notakto: NotaktoGame::new(Notakto::new(3).expect("three boards are allowed")),
+            notakto_computer: false,
+            gravity: MnkEngine::connect_four(),
+            falling: None,
+            quantum: QuantumEngine::new(),
+            quantum_first: None,
+            qubic: QubicGame::default(),
+            qubic_computer: false,
+            seat_choices: default_seat_choices(),
+            seats: [0, 0],
+            agents: [Box::<HumanPlayer>::default(), Box::<HumanPlayer>::default()],
+            show_hints: false,
+            rules: RuleSet::Standard,
+            analysis: false,
         }
     }
 }
@@ -23,6 +138,7 @@ impl Default for TicTacToeApp {
 impl App for TicTacToeApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.apply_terminal_theme(ctx);
+        self.play_agent_move();
 
         egui::CentralPanel::default().show(ctx, |ui| {
             // 1. Fixed Header Area (Prevents board from moving)
@@ -50,23 +166,48 @@ impl App for TicTacToeApp {
 
             // 3. Footer
             ui.add_space(30.0);
+            self.render_controls(ui);
+            ui.add_space(10.0);
             ui.vertical_centered(|ui| {
-                if ui
-                    .add(
-                        egui::Button::new(egui::RichText::new("NEW GAME").size(16.0).strong())
-                            .fill(egui::Color32::from_rgb(50, 50, 50))
-                            .min_size(egui::vec2(120.0, 40.0)),
-                    )
-                    .clicked()
-                {
-                    self.engine.reset();
+                self.render_mode_picker(ui);
+                match self.mode {
+                    Mode::Notakto => self.render_notakto_options(ui),
+                    Mode::Qubic => {
+                        ui.checkbox(&mut self.qubic_computer, "COMPUTER");
+                    }
+                    _ => self.render_symbol_picker(ui),
                 }
+                self.render_rules_picker(ui);
+                ui.add_enabled_ui(self.analysis_available(), |ui| {
+                    ui.checkbox(&mut self.analysis, "ANALYSIS")
+                        .on_disabled_hover_text("STANDARD CLASSIC GAME ONLY");
+                });
+                self.render_seat_picker(ui, Player::X);
+                self.render_seat_picker(ui, Player::O);
             });
         });
+
+        if self.is_agent_turn() {
+            ctx.request_repaint();
+        }
     }
 }
 
 impl TicTacToeApp {
+    /// Offer another player for the seats of the classic game, such as a remote engine.
+    /// `make` builds it from a seed each time it is seated.
+    pub fn with_seat_choice(
+        mut self,
+        name: impl Into<String>,
+        make: impl Fn(u64) -> Box<dyn Agent> + 'static,
+    ) -> Self {
+        self.seat_choices.push(SeatChoice {
+            name: name.into(),
+            make: Box::new(make),
+        });
+        self
+    }
+
     fn apply_terminal_theme(&self, ctx: &egui::Context) {
         let mut visuals = egui::Visuals::dark();
 
@@ -83,9 +224,298 @@ impl TicTacToeApp {
         ctx.set_style(style);
     }
 
+    fn render_controls(&mut self, ui: &mut egui::Ui) {
+        const BUTTON_WIDTH: f32 = 100.0;
+        let button = |text: &str| {
+            egui::Button::new(egui::RichText::new(text).size(16.0).strong())
+                .fill(egui::Color32::from_rgb(50, 50, 50))
+                .min_size(egui::vec2(BUTTON_WIDTH, 40.0))
+        };
+
+        let total_width = (BUTTON_WIDTH * 4.0) + (CELL_GAP * 3.0);
+        let horizontal_padding = (ui.available_width() - total_width) / 2.0;
+
+        // Only the classic engine offers hints, and ultimate keeps no move history
+        let classic = self.mode == Mode::Classic;
+        let (can_undo, can_redo) = match self.mode {
+            Mode::Classic => (self.engine.can_undo(), self.engine.can_redo()),
+            Mode::Ultimate | Mode::Gravity | Mode::Quantum => (false, false),
+            Mode::Wild => (self.wild.can_undo(), self.wild.can_redo()),
+            Mode::Notakto => (self.notakto.can_undo(), self.notakto.can_redo()),
+            Mode::Qubic => (self.qubic.can_undo(), self.qubic.can_redo()),
+        };
+
+        ui.horizontal(|ui| {
+            ui.add_space(horizontal_padding);
+            ui.spacing_mut().item_spacing.x = CELL_GAP;
+
+            if ui.add_enabled(can_undo, button("UNDO")).clicked() {
+                self.undo();
+            }
+            if ui
+                .add_enabled(classic, button("HINT").selected(self.show_hints))
+                .clicked()
+            {
+                self.show_hints = !self.show_hints;
+            }
+            if ui.add(button("NEW GAME")).clicked() {
+                match self.mode {
+                    Mode::Classic => self.engine.reset(),
+                    Mode::Ultimate => self.ultimate.reset(),
+                    Mode::Wild => self.wild.reset(),
+                    Mode::Notakto => self.notakto.reset(),
+                    Mode::Gravity => {
+                        self.gravity.reset();
+                        self.falling = None;
+                    }
+                    Mode::Quantum => {
+                        self.quantum.reset();
+                        self.quantum_first = None;
+                    }
+                    Mode::Qubic => self.qubic.reset(),
+                }
+            }
+            if ui.add_enabled(can_redo, button("REDO")).clicked() {
+                self.redo();
+            }
+        });
+    }
+
+    /// Takes back a move. Against the computer, its replies are taken back too so a
+    /// human is to move again.
+    fn undo(&mut self) {
+        match self.mode {
+            Mode::Classic => {
+                self.engine.undo();
+                while self.engine.can_undo() && self.is_agent_turn() {
+                    self.engine.undo();
+                }
+            }
+            Mode::Ultimate | Mode::Gravity | Mode::Quantum => {}
+            Mode::Wild => {
+                self.wild.undo();
+            }
+            Mode::Notakto => {
+                self.notakto.undo();
+                while self.notakto.can_undo() && self.is_agent_turn() {
+                    self.notakto.undo();
+                }
+            }
+            Mode::Qubic => {
+                self.qubic.undo();
+                while self.qubic.can_undo() && self.is_agent_turn() {
+                    self.qubic.undo();
+                }
+            }
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.mode {
+            Mode::Classic => {
+                self.engine.redo();
+                while self.engine.can_redo() && self.is_agent_turn() {
+                    self.engine.redo();
+                }
+            }
+            Mode::Ultimate | Mode::Gravity | Mode::Quantum => {}
+            Mode::Wild => {
+                self.wild.redo();
+            }
+            Mode::Notakto => {
+                self.notakto.redo();
+                while self.notakto.can_redo() && self.is_agent_turn() {
+                    self.notakto.redo();
+                }
+            }
+            Mode::Qubic => {
+                self.qubic.redo();
+                while self.qubic.can_redo() && self.is_agent_turn() {
+                    self.qubic.redo();
+                }
+            }
+        }
+    }
+
+    fn render_mode_picker(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("MODE")
+            .selected_text(self.mode.label())
+            .show_ui(ui, |ui| {
+                for mode in [
+                    Mode::Classic,
+                    Mode::Ultimate,
+                    Mode::Wild,
+                    Mode::Notakto,
+                    Mode::Gravity,
+                    Mode::Quantum,
+                    Mode::Qubic,
+                ] {
+                    ui.selectable_value(&mut self.mode, mode, mode.label());
+                }
+            });
+    }
+
+    /// Wild players choose a symbol before each move.
+    fn render_symbol_picker(&mut self, ui: &mut egui::Ui) {
+        ui.add_enabled_ui(self.mode == Mode::Wild, |ui| {
+            ui.horizontal(|ui| {
+                for symbol in [Player::X, Player::O] {
+                    ui.selectable_value(
+                        &mut self.wild_symbol,
+                        symbol,
+                        format!("PLACE {:?}", symbol),
+                    );
+                }
+            });
+        });
+    }
+
+    /// How many boards to play on, which starts a new game, and whether the computer
+    /// plays second.
+    fn render_notakto_options(&mut self, ui: &mut egui::Ui) {
+        let mut boards = self.notakto.rules().boards();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("BOARDS")
+                .selected_text(boards.to_string())
+                .show_ui(ui, |ui| {
+                    for count in 1..=MAX_NOTAKTO_BOARDS {
+                        ui.selectable_value(&mut boards, count, count.to_string());
+                    }
+                });
+            ui.checkbox(&mut self.notakto_computer, "COMPUTER");
+        });
+        if boards != self.notakto.rules().boards()
+            && let Some(rules) = Notakto::new(boards)
+        {
+            self.notakto = NotaktoGame::new(rules);
+        }
+    }
+
+    fn render_rules_picker(&mut self, ui: &mut egui::Ui) {
+        let label = |rules: RuleSet| match rules {
+            RuleSet::Standard => "STANDARD",
+            RuleSet::Misere => "MISÈRE",
+        };
+        let previous = self.rules;
+        ui.add_enabled_ui(self.mode == Mode::Classic, |ui| {
+            egui::ComboBox::from_label("RULES")
+                .selected_text(label(self.rules))
+                .show_ui(ui, |ui| {
+                    for rules in RuleSet::ALL {
+                        ui.selectable_value(&mut self.rules, rules, label(rules));
+                    }
+                });
+        });
+        if self.rules != previous {
+            self.engine = GameEngine::with_rules(self.rules);
+        }
+    }
+
+    /// The tablebase behind the analysis overlay only solves the standard classic game.
+    fn analysis_available(&self) -> bool {
+        self.mode == Mode::Classic && self.rules == RuleSet::Standard
+    }
+
+    fn render_seat_picker(&mut self, ui: &mut egui::Ui, player: Player) {
+        let seat = seat_index(player);
+        let previous = self.seats[seat];
+
+        // The computer only plays the classic game
+        ui.add_enabled_ui(self.mode == Mode::Classic, |ui| {
+            egui::ComboBox::from_label(format!("{:?}", player))
+                .selected_text(&self.seat_choices[self.seats[seat]].name)
+                .show_ui(ui, |ui| {
+                    for (index, choice) in self.seat_choices.iter().enumerate() {
+                        ui.selectable_value(&mut self.seats[seat], index, &choice.name);
+                    }
+                });
+        });
+
+        if self.seats[seat] != previous {
+            // Seed from the frame clock so each new computer player plays differently.
+            let seed = ui.input(|i| (i.time * 1_000_000.0) as u64) ^ seat as u64;
+            self.agents[seat] = (self.seat_choices[self.seats[seat]].make)(seed);
+        }
+    }
+
+    fn is_agent_turn(&self) -> bool {
+        match self.mode {
+            Mode::Classic => {
+                self.engine.status == GameStatus::Ongoing
+                    && !self.agents[seat_index(self.engine.current_player)].takes_input()
+            }
+            Mode::Notakto => {
+                self.notakto_computer
+                    && *self.notakto.status() == GameStatus::Ongoing
+                    && self.notakto.current_player() == Player::O
+            }
+            Mode::Qubic => {
+                self.qubic_computer
+                    && *self.qubic.status() == GameStatus::Ongoing
+                    && self.qubic.current_player() == Player::O
+            }
+            Mode::Ultimate | Mode::Wild | Mode::Gravity | Mode::Quantum => false,
+        }
+    }
+
+    /// Let the computer move whenever it is seated on the side to move. In the classic
+    /// game a human seat plays the cell last clicked for it the same way.
+    fn play_agent_move(&mut self) {
+        match self.mode {
+            Mode::Classic if self.engine.status == GameStatus::Ongoing => {
+                let seat = seat_index(self.engine.current_player);
+                if let Some(pos) = self.agents[seat].choose_move(&self.engine) {
+                    let _ = self.engine.play_move(pos);
+                }
+            }
+            Mode::Notakto if self.is_agent_turn() => {
+                if let Some(mv) = notakto::best_move(&self.notakto) {
+                    let _ = self.notakto.play_move(mv);
+                }
+            }
+            Mode::Qubic if self.is_agent_turn() => {
+                if let Some(pos) = qubic::best_move(&self.qubic) {
+                    let _ = self.qubic.play_move(pos);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn render_status(&self, ui: &mut egui::Ui) {
-        let (text, color) = match self.engine.status {
-            GameStatus::Ongoing => match self.engine.current_player {
+        let (status, current_player) = match self.mode {
+            Mode::Classic => (
+                without_line(&self.engine.status),
+                self.engine.current_player,
+            ),
+            Mode::Ultimate => (
+                without_line(&self.ultimate.status),
+                self.ultimate.current_player,
+            ),
+            Mode::Gravity => (
+                without_line(&self.gravity.status),
+                self.gravity.current_player,
+            ),
+            Mode::Qubic => (
+                without_line(self.qubic.status()),
+                self.qubic.current_player(),
+            ),
+            Mode::Wild => {
+                render_turn_order_status(ui, self.wild.status(), self.wild.current_player());
+                return;
+            }
+            Mode::Notakto => {
+                let current_player = self.notakto.current_player();
+                render_turn_order_status(ui, self.notakto.status(), current_player);
+                return;
+            }
+            Mode::Quantum => {
+                render_quantum_status(ui, &self.quantum);
+                return;
+            }
+        };
+        let (text, color) = match status {
+            GameStatus::Ongoing => match current_player {
                 Player::X => ("X'S TURN", egui::Color32::from_rgb(255, 85, 85)),
                 Player::O => ("O'S TURN", egui::Color32::from_rgb(85, 170, 255)),
             },
@@ -113,63 +543,689 @@ impl TicTacToeApp {
             // Push the grid to the center by adding space on the left
             ui.add_space(horizontal_padding);
 
-            egui::Grid::new("ttt_grid")
-                .spacing(egui::vec2(CELL_GAP, CELL_GAP))
-                .show(ui, |ui| {
-                    let winning_line = if let GameStatus::Win { line, .. } = self.engine.status {
-                        Some(line)
+            match self.mode {
+                Mode::Classic => self.render_classic_grid(ui, cell_size),
+                Mode::Ultimate => self.render_ultimate_grid(ui),
+                Mode::Wild => self.render_wild_grid(ui, cell_size),
+                Mode::Notakto => self.render_notakto_boards(ui),
+                Mode::Gravity => self.render_gravity_grid(ui),
+                Mode::Quantum => self.render_quantum_grid(ui, cell_size),
+                Mode::Qubic => self.render_qubic_layers(ui),
+            }
+        });
+    }
+
+    fn render_classic_grid(&mut self, ui: &mut egui::Ui, cell_size: f32) {
+        egui::Grid::new("ttt_grid")
+            .spacing(egui::vec2(CELL_GAP, CELL_GAP))
+            .show(ui, |ui| {
+                let winning_line = if let GameStatus::Win { line, .. } = self.engine.status {
+                    Some(line)
+                } else {
+                    None
+                };
+                let hints = if self.show_hints && !self.is_agent_turn() {
+                    self.engine.hints()
+                } else {
+                    Hints::default()
+                };
+                let player = self.engine.current_player;
+                let evaluations = if self.analysis && self.analysis_available() {
+                    tablebase().move_values(&self.engine).unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                for row in 0..3 {
+                    for col in 0..3 {
+                        let idx = row * 3 + col;
+                        let hinted = |moves: &[Position]| moves.iter().any(|p| p.to_index() == idx);
+                        let highlight = if winning_line.is_some_and(|l| l.contains(&idx)) {
+                            Some(player_color(self.engine.board[idx]))
+                        } else if hinted(&hints.wins) {
+                            Some(player_color(Some(player)))
+                        } else if hinted(&hints.blocks) {
+                            Some(player_color(Some(player.next())))
+                        } else if hinted(&hints.forks) {
+                            Some(FORK_HINT_COLOR)
+                        } else {
+                            None
+                        };
+                        let evaluation = evaluations
+                            .iter()
+                            .find(|(pos, _)| pos.to_index() == idx)
+                            .map(|&(_, entry)| entry);
+                        self.render_cell(ui, idx, highlight, evaluation, cell_size);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Nine small boards, each framed so the board in play stands out.
+    fn render_ultimate_grid(&mut self, ui: &mut egui::Ui) {
+        let board_size = (BOARD_SIZE - (2.0 * CELL_GAP)) / 3.0;
+        let cell_size = (board_size - (2.0 * SUB_CELL_GAP) - (2.0 * SUB_CELL_GAP)) / 3.0;
+
+        let meta_line = if let GameStatus::Win { line, .. } = self.ultimate.status {
+            Some(line)
+        } else {
+            None
+        };
+
+        egui::Grid::new("ultimate_grid")
+            .spacing(egui::vec2(CELL_GAP, CELL_GAP))
+            .show(ui, |ui| {
+                for board_row in 0..3 {
+                    for board_col in 0..3 {
+                        let board = board_row * 3 + board_col;
+                        let small = self.ultimate.boards[board];
+                        let is_active = self.ultimate.status == GameStatus::Ongoing
+                            && small.status == GameStatus::Ongoing
+                            && self
+                                .ultimate
+                                .active_board
+                                .is_none_or(|active| active.to_index() == board);
+
+                        let mut frame = egui::Frame::none()
+                            .rounding(CORNER_RADIUS)
+                            .inner_margin(SUB_CELL_GAP);
+                        frame = match small.status {
+                            GameStatus::Win { player, .. } => {
+                                frame.fill(player_color(Some(player)).gamma_multiply(0.25))
+                            }
+                            _ if is_active => {
+                                frame.fill(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20))
+                            }
+                            _ => frame.fill(egui::Color32::from_rgb(25, 25, 30)),
+                        };
+                        if meta_line.is_some_and(|l| l.contains(&board)) {
+                            let winner = self.ultimate.meta_board()[board];
+                            frame = frame.stroke(egui::Stroke::new(2.0, player_color(winner)));
+                        }
+
+                        frame.show(ui, |ui| {
+                            egui::Grid::new(("ultimate_board", board))
+                                .spacing(egui::vec2(SUB_CELL_GAP, SUB_CELL_GAP))
+                                .show(ui, |ui| {
+                                    let winning_line =
+                                        if let GameStatus::Win { line, .. } = small.status {
+                                            Some(line)
+                                        } else {
+                                            None
+                                        };
+                                    for row in 0..3 {
+                                        for col in 0..3 {
+                                            let cell = row * 3 + col;
+                                            let is_win =
+                                                winning_line.is_some_and(|l| l.contains(&cell));
+                                            self.render_ultimate_cell(
+                                                ui, board, cell, is_win, cell_size,
+                                            );
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_wild_grid(&mut self, ui: &mut egui::Ui, cell_size: f32) {
+        let winning_line = if let GameStatus::Win { line, .. } = *self.wild.status() {
+            Some(line)
+        } else {
+            None
+        };
+        egui::Grid::new("wild_grid")
+            .spacing(egui::vec2(CELL_GAP, CELL_GAP))
+            .show(ui, |ui| {
+                for row in 0..3 {
+                    for col in 0..3 {
+                        let idx = row * 3 + col;
+                        let symbol = self.wild.board()[idx];
+                        let is_win = winning_line.is_some_and(|l| l.contains(&idx));
+                        let button =
+                            cell_button(symbol, is_win.then(|| player_color(symbol)), cell_size);
+
+                        let Some(mv) = WildMove::new(idx as u8, self.wild_symbol) else {
+                            continue;
+                        };
+                        let can_click = self.wild.validate_move(mv).is_ok();
+                        if ui
+                            .add_enabled(can_click, button)
+                            .is_pointer_button_down_on()
+                            && can_click
+                        {
+                            let _ = self.wild.play_move(mv);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// The boards side by side, as wide together as the classic board. Dead boards
+    /// are greyed out, and the line that ended the game is outlined.
+    fn render_notakto_boards(&mut self, ui: &mut egui::Ui) {
+        let count = self.notakto.board().len();
+        let board_size = (BOARD_SIZE - (count - 1) as f32 * CELL_GAP) / count as f32;
+        let cell_size = (board_size - (2.0 * SUB_CELL_GAP) - (2.0 * SUB_CELL_GAP)) / 3.0;
+        let last_line = if let GameStatus::Win { line, .. } = *self.notakto.status() {
+            Some(line)
+        } else {
+            None
+        };
+        let can_play = !self.is_agent_turn();
+
+        ui.spacing_mut().item_spacing.x = CELL_GAP;
+        for board in 0..count {
+            let cells = self.notakto.board()[board];
+            let dead = notakto::is_dead(&cells);
+            let frame = egui::Frame::none()
+                .rounding(CORNER_RADIUS)
+                .inner_margin(SUB_CELL_GAP)
+                .fill(if dead {
+                    egui::Color32::from_rgb(25, 25, 30)
+                } else {
+                    egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20)
+                });
+
+            frame.show(ui, |ui| {
+                egui::Grid::new(("notakto_board", board))
+                    .spacing(egui::vec2(SUB_CELL_GAP, SUB_CELL_GAP))
+                    .show(ui, |ui| {
+                        for row in 0..3 {
+                            for col in 0..3 {
+                                let cell = row * 3 + col;
+                                let color = if dead {
+                                    egui::Color32::GRAY
+                                } else {
+                                    player_color(Some(Player::X))
+                                };
+                                let text = egui::RichText::new(if cells[cell] { "X" } else { "" })
+                                    .size(cell_size * 0.6)
+                                    .strong()
+                                    .color(color);
+                                let is_line = last_line
+                                    .is_some_and(|(b, line)| b == board && line.contains(&cell));
+                                let button = board_button(
+                                    text,
+                                    is_line.then(|| player_color(Some(Player::X))),
+                                    cell_size,
+                                );
+
+                                let Some(mv) = NotaktoMove::new(board, cell as u8) else {
+                                    continue;
+                                };
+                                let can_click = can_play && self.notakto.validate_move(mv).is_ok();
+                                if ui
+                                    .add_enabled(can_click, button)
+                                    .is_pointer_button_down_on()
+                                    && can_click
+                                {
+                                    let _ = self.notakto.play_move(mv);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        }
+    }
+
+    /// Clicking anywhere in a column drops a piece there. The piece is played at once
+    /// but drawn falling into place, and further drops wait until it lands.
+    fn render_gravity_grid(&mut self, ui: &mut egui::Ui) {
+        let (width, height) = (self.gravity.width(), self.gravity.height());
+        let cell_size = (BOARD_SIZE - (width - 1) as f32 * SUB_CELL_GAP) / width as f32;
+        let now = ui.input(|i| i.time);
+        let falling = self
+            .falling
+            .filter(|falling| now - falling.started < DROP_SECONDS);
+        let winning_line = match &self.gravity.status {
+            GameStatus::Win { line, .. } => line.clone(),
+            _ => Vec::new(),
+        };
+
+        let mut rects = Vec::with_capacity(width * height);
+        egui::Grid::new("gravity_grid")
+            .spacing(egui::vec2(SUB_CELL_GAP, SUB_CELL_GAP))
+            .show(ui, |ui| {
+                for row in 0..height {
+                    for col in 0..width {
+                        let index = row * width + col;
+                        // The falling piece is drawn separately until it lands
+                        let cell = match falling {
+                            Some(falling) if falling.index == index => None,
+                            _ => self.gravity.board()[index],
+                        };
+                        let is_win = winning_line.contains(&index);
+                        let button =
+                            cell_button(cell, is_win.then(|| player_color(cell)), cell_size);
+
+                        let target = self.gravity.drop_target(col);
+                        let can_click = falling.is_none()
+                            && self.gravity.status == GameStatus::Ongoing
+                            && target.is_some();
+                        let response = ui.add_enabled(can_click, button);
+                        rects.push(response.rect);
+                        if response.clicked()
+                            && let Some(target) = target
+                            && self.gravity.play_move(target).is_ok()
+                        {
+                            self.falling = Some(Falling {
+                                index: target,
+                                started: now,
+                            });
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(falling) = falling {
+            // Fall from the top of the column, speeding up on the way down
+            let t = ((now - falling.started) / DROP_SECONDS) as f32;
+            let from = rects[falling.index % width].center();
+            let to = rects[falling.index].center();
+            let piece = self.gravity.board()[falling.index];
+            ui.painter().text(
+                from + (to - from) * t * t,
+                egui::Align2::CENTER_CENTER,
+                match piece {
+                    Some(Player::X) => "X",
+                    Some(Player::O) => "O",
+                    None => "",
+                },
+                egui::FontId::proportional(cell_size * 0.6),
+                player_color(piece),
+            );
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// A quantum mark takes two clicks, one per square. While a cycle waits to be
+    /// collapsed, its two squares are outlined and clicking one settles it there.
+    fn render_quantum_grid(&mut self, ui: &mut egui::Ui, cell_size: f32) {
+        let (line, runner_up) = match self.quantum.status {
+            QuantumStatus::Win {
+                line, runner_up, ..
+            } => (Some(line), runner_up),
+            _ => (None, None),
+        };
+        let single = match self.quantum.open_squares()[..] {
+            [square] => Some(square),
+            _ => None,
+        };
+
+        egui::Grid::new("quantum_grid")
+            .spacing(egui::vec2(CELL_GAP, CELL_GAP))
+            .show(ui, |ui| {
+                for square in (0..9).filter_map(Position::new) {
+                    let idx = square.to_index();
+                    let classical = self.quantum.classical[idx];
+                    // `None` picks the first square of a mark, which is only a selection
+                    let mv = match (self.quantum.cycle, single, self.quantum_first) {
+                        (Some(_), _, _) => Some(QuantumMove::Collapse(square)),
+                        (None, Some(_), _) => Some(QuantumMove::Classical(square)),
+                        (None, None, Some(first)) if first != square => {
+                            Some(QuantumMove::Spooky(first, square))
+                        }
+                        _ => None,
+                    };
+                    let can_click = match mv {
+                        Some(mv) => self.quantum.validate_move(mv).is_ok(),
+                        None => {
+                            self.quantum.status == QuantumStatus::Ongoing && classical.is_none()
+                        }
+                    };
+
+                    let highlight = if line.is_some_and(|l| l.contains(&idx))
+                        || runner_up.is_some_and(|l| l.contains(&idx))
+                    {
+                        Some(player_color(classical.map(|mark| mark.player)))
+                    } else if let Some(cycle) = self.quantum.cycle
+                        && can_click
+                    {
+                        Some(player_color(Some(cycle.mark.player)))
+                    } else if self.quantum_first == Some(square) {
+                        Some(egui::Color32::LIGHT_GRAY)
                     } else {
                         None
                     };
+                    let button = match classical {
+                        Some(mark) => {
+                            let text =
+                                egui::RichText::new(format!("{:?}{}", mark.player, mark.turn))
+                                    .size(cell_size * 0.4)
+                                    .strong()
+                                    .color(player_color(Some(mark.player)));
+                            board_button(text, highlight, cell_size)
+                        }
+                        None => {
+                            let marks: Vec<Mark> = self.quantum.marks_in(square).collect();
+                            spooky_button(&marks, highlight, cell_size)
+                        }
+                    };
 
-                    for row in 0..3 {
-                        for col in 0..3 {
-                            let idx = row * 3 + col;
-                            let is_win =
-                                winning_line.map_or(false, |l| l.contains(&(idx as usize)));
-                            self.render_cell(ui, idx, is_win, cell_size);
+                    if ui.add_enabled(can_click, button).clicked() && can_click {
+                        match mv {
+                            Some(mv) => {
+                                let _ = self.quantum.play_move(mv);
+                                self.quantum_first = None;
+                            }
+                            // Clicking the selected square again deselects it
+                            None => {
+                                self.quantum_first =
+                                    (self.quantum_first != Some(square)).then_some(square);
+                            }
                         }
+                    }
+                    if idx % 3 == 2 {
                         ui.end_row();
                     }
+                }
+            });
+    }
+
+    /// The four layers of the cube side by side, lowest first. A winning line is
+    /// outlined in every layer it passes through.
+    fn render_qubic_layers(&mut self, ui: &mut egui::Ui) {
+        let layer_size = (BOARD_SIZE - 3.0 * CELL_GAP) / 4.0;
+        let cell_size = (layer_size - 2.0 * QUBIC_CELL_GAP - 3.0 * QUBIC_CELL_GAP) / 4.0;
+        let winning_line = if let GameStatus::Win { line, .. } = *self.qubic.status() {
+            Some(line)
+        } else {
+            None
+        };
+        let can_play = !self.is_agent_turn();
+
+        ui.spacing_mut().item_spacing.x = CELL_GAP;
+        for layer in 0..4 {
+            ui.vertical(|ui| {
+                ui.label(
+                    egui::RichText::new(format!("LAYER {}", layer))
+                        .size(11.0)
+                        .color(egui::Color32::GRAY),
+                );
+                let frame = egui::Frame::none()
+                    .rounding(CORNER_RADIUS / 2.0)
+                    .inner_margin(QUBIC_CELL_GAP)
+                    .fill(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20));
+                frame.show(ui, |ui| {
+                    egui::Grid::new(("qubic_layer", layer))
+                        .spacing(egui::vec2(QUBIC_CELL_GAP, QUBIC_CELL_GAP))
+                        .show(ui, |ui| {
+                            for row in 0..4 {
+                                for col in 0..4 {
+                                    let Some(pos) = CubePosition::new(layer, row, col) else {
+                                        continue;
+                                    };
+                                    let symbol = self.qubic.board().get(pos);
+                                    let is_win = winning_line
+                                        .is_some_and(|line| line.contains(&pos.to_index()));
+                                    let button = cell_button(
+                                        symbol,
+                                        is_win.then(|| player_color(symbol)),
+                                        cell_size,
+                                    );
+
+                                    let can_click =
+                                        can_play && self.qubic.validate_move(pos).is_ok();
+                                    if ui
+                                        .add_enabled(can_click, button)
+                                        .is_pointer_button_down_on()
+                                        && can_click
+                                    {
+                                        let _ = self.qubic.play_move(pos);
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
                 });
-        });
+            });
+        }
     }
 
-    fn render_cell(&mut self, ui: &mut egui::Ui, idx: usize, is_win: bool, size: f32) {
-        let cell = self.engine.board[idx];
-        let (symbol, color) = match cell {
-            Some(Player::X) => ("X", egui::Color32::from_rgb(255, 85, 85)),
-            Some(Player::O) => ("O", egui::Color32::from_rgb(85, 170, 255)),
-            None => ("", egui::Color32::TRANSPARENT),
+    fn render_ultimate_cell(
+        &mut self,
+        ui: &mut egui::Ui,
+        board: usize,
+        cell: usize,
+        is_win: bool,
+        size: f32,
+    ) {
+        let Some(pos) = UltimatePosition::new(board as u8, cell as u8) else {
+            return;
         };
+        let symbol = self.ultimate.boards[board].board[cell];
+        let button = cell_button(symbol, is_win.then(|| player_color(symbol)), size);
 
-        let mut button = egui::Button::new(
-            egui::RichText::new(symbol)
-                .size(size * 0.6)
-                .strong()
-                .color(color),
-        )
-        .min_size(egui::vec2(size, size));
-
-        // Highlight winning line with a subtle glow, otherwise keep dark
-        if is_win {
-            button = button.fill(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20));
-            button = button.stroke(egui::Stroke::new(2.0, color));
-        } else {
-            button = button.fill(egui::Color32::from_rgb(35, 35, 40));
+        let can_click = self.ultimate.validate_move(pos).is_ok();
+        if ui
+            .add_enabled(can_click, button)
+            .is_pointer_button_down_on()
+            && can_click
+        {
+            let _ = self.ultimate.play_move(pos);
         }
+    }
 
-        let can_click = self.engine.status == GameStatus::Ongoing && cell.is_none();
+    fn render_cell(
+        &mut self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        highlight: Option<egui::Color32>,
+        evaluation: Option<Entry>,
+        size: f32,
+    ) {
+        let cell = self.engine.board[idx];
+        let button = match evaluation {
+            Some(entry) => evaluation_button(entry, self.engine.current_player, highlight, size),
+            None => cell_button(cell, highlight, size),
+        };
+
+        let legal = self.engine.legal_moves().find(|pos| pos.to_index() == idx);
+        let can_click = legal.is_some() && !self.is_agent_turn();
         if ui
             .add_enabled(can_click, button)
             .is_pointer_button_down_on()
             && can_click
+            && let Some(pos) = legal
         {
-            {
-                if let Some(pos) = Position::new(idx as u8) {
-                    let _ = self.engine.play_move(pos);
-                }
-            }
+            // Played by the human's seat on the next frame
+            self.agents[seat_index(self.engine.current_player)].input(pos);
+            ui.ctx().request_repaint();
         }
     }
 }
+
+/// A human, each difficulty of the minimax player, and the other built-in agents.
+fn default_seat_choices() -> Vec<SeatChoice> {
+    let mut choices = vec![SeatChoice {
+        name: "HUMAN".to_string(),
+        make: Box::new(|_| Box::<HumanPlayer>::default()),
+    }];
+    for difficulty in Difficulty::ALL {
+        choices.push(SeatChoice {
+            name: difficulty.name().to_uppercase(),
+            make: Box::new(move |seed| Box::new(AiPlayer::new(difficulty, seed))),
+        });
+    }
+    choices.push(SeatChoice {
+        name: "HEURISTIC".to_string(),
+        make: Box::new(|_| Box::new(HeuristicPlayer)),
+    });
+    choices.push(SeatChoice {
+        name: "MCTS".to_string(),
+        make: Box::new(|seed| Box::new(Mcts::new(Budget::Iterations(MCTS_PLAYOUTS), seed))),
+    });
+    choices
+}
+
+fn seat_index(player: Player) -> usize {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+    }
+}
+
+/// For games where both players may place the same symbol, the players go by their
+/// turn order instead.
+fn render_turn_order_status<L>(ui: &mut egui::Ui, status: &GameStatus<L>, current_player: Player) {
+    let number = |player: Player| seat_index(player) + 1;
+    let text = match status {
+        GameStatus::Ongoing => format!("PLAYER {}'S TURN", number(current_player)),
+        GameStatus::Win { player, .. } => format!("PLAYER {} WINS!", number(*player)),
+        GameStatus::Draw => "DRAW".to_string(),
+    };
+    ui.label(
+        egui::RichText::new(text)
+            .size(20.0)
+            .strong()
+            .color(egui::Color32::LIGHT_GRAY),
+    );
+}
+
+/// Quantum games also say who must collapse a cycle, and score simultaneous lines.
+fn render_quantum_status(ui: &mut egui::Ui, engine: &QuantumEngine) {
+    let name = |player: Player| format!("{:?}", player);
+    let (text, color) = match engine.status {
+        QuantumStatus::Ongoing => {
+            let player = engine.current_player;
+            let text = match engine.cycle {
+                Some(_) => format!("{} CHOOSES THE COLLAPSE", name(player)),
+                None => format!("{}'S TURN", name(player)),
+            };
+            (text, player_color(Some(player)))
+        }
+        QuantumStatus::Win {
+            player,
+            runner_up: None,
+            ..
+        } => (
+            format!("{} WINS!", name(player)),
+            player_color(Some(player)),
+        ),
+        QuantumStatus::Win { player, .. } => (
+            format!("{} 1 : {} ½", name(player), name(player.next())),
+            player_color(Some(player)),
+        ),
+        QuantumStatus::Draw => ("DRAW".to_string(), egui::Color32::LIGHT_GRAY),
+    };
+    ui.label(egui::RichText::new(text).size(20.0).strong().color(color));
+}
+
+/// The outcome of a game without the winning line, which differs between variants.
+fn without_line<L>(status: &GameStatus<L>) -> GameStatus<()> {
+    match status {
+        GameStatus::Win { player, .. } => GameStatus::Win {
+            player: *player,
+            line: (),
+        },
+        GameStatus::Draw => GameStatus::Draw,
+        GameStatus::Ongoing => GameStatus::Ongoing,
+    }
+}
+
+fn player_color(player: Option<Player>) -> egui::Color32 {
+    match player {
+        Some(Player::X) => egui::Color32::from_rgb(255, 85, 85),
+        Some(Player::O) => egui::Color32::from_rgb(85, 170, 255),
+        None => egui::Color32::TRANSPARENT,
+    }
+}
+
+/// A square board cell showing `cell`'s symbol, glowing in the `highlight` colour if
+/// it is part of a winning line or a hinted move.
+fn cell_button(
+    cell: Option<Player>,
+    highlight: Option<egui::Color32>,
+    size: f32,
+) -> egui::Button<'static> {
+    let symbol = match cell {
+        Some(Player::X) => "X",
+        Some(Player::O) => "O",
+        None => "",
+    };
+    let text = egui::RichText::new(symbol)
+        .size(size * 0.6)
+        .strong()
+        .color(player_color(cell));
+    board_button(text, highlight, size)
+}
+
+/// A square holding spooky marks, each in its player's colour with its move number.
+fn spooky_button(
+    marks: &[Mark],
+    highlight: Option<egui::Color32>,
+    size: f32,
+) -> egui::Button<'static> {
+    let mut job = egui::text::LayoutJob::default();
+    job.wrap.max_width = size * 0.8;
+    for mark in marks {
+        let symbol = match mark.player {
+            Player::X => "x",
+            Player::O => "o",
+        };
+        job.append(
+            &format!("{}{} ", symbol, mark.turn),
+            0.0,
+            egui::TextFormat {
+                font_id: egui::FontId::proportional(size * 0.18),
+                color: player_color(Some(mark.player)),
+                ..Default::default()
+            },
+        );
+    }
+    board_button(job, highlight, size)
+}
+
+/// An empty cell labelled with what playing there leads to for `player` with perfect
+/// play afterwards, and how many moves until the game ends.
+fn evaluation_button(
+    entry: Entry,
+    player: Player,
+    highlight: Option<egui::Color32>,
+    size: f32,
+) -> egui::Button<'static> {
+    // The entry's distance counts the moves after this one
+    let moves = entry.distance + 1;
+    let (text, color) = match (entry.value, player) {
+        (Value::Draw, _) => ("DRAW".to_string(), egui::Color32::LIGHT_GRAY),
+        (Value::XWins, Player::X) | (Value::OWins, Player::O) => (
+            format!("WIN\nIN {}", moves),
+            egui::Color32::from_rgb(90, 200, 120),
+        ),
+        _ => (
+            format!("LOSS\nIN {}", moves),
+            egui::Color32::from_rgb(230, 140, 60),
+        ),
+    };
+    let text = egui::RichText::new(text)
+        .size(size * 0.16)
+        .strong()
+        .color(color);
+    board_button(text, highlight, size)
+}
+
+fn board_button(
+    text: impl Into<egui::WidgetText>,
+    highlight: Option<egui::Color32>,
+    size: f32,
+) -> egui::Button<'static> {
+    let button = egui::Button::new(text).min_size(egui::vec2(size, size));
+
+    // Highlight winning line and hints with a subtle glow, otherwise keep dark
+    if let Some(glow) = highlight {
+        button
+            .fill(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20))
+            .stroke(egui::Stroke::new(2.0, glow))
+    } else {
+        button.fill(egui::Color32::from_rgb(35, 35, 40))
+    }
+}
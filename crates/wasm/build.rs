@@ -0,0 +1,6 @@
+// Solve the game at build time so the module can answer perfect-play queries without
+// searching in the browser.
+
+fn main() {
+    rust_tac_toe_engine::tablebase::write_for_build();
+}
@@ -5,12 +5,49 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 // Export engine types and functions
 pub use rust_tac_toe_engine::{GameEngine, Position};
 
+use rust_tac_toe_engine::ai::{AiPlayer, Difficulty, best_move};
+use rust_tac_toe_engine::mcts::{Budget, Mcts};
+use rust_tac_toe_engine::mnk::MnkEngine;
+use rust_tac_toe_engine::notakto::{self, Notakto, NotaktoGame, NotaktoMove};
+use rust_tac_toe_engine::rules::RuleSet;
+use rust_tac_toe_engine::tablebase::Tablebase;
+use rust_tac_toe_engine::wild::{WildGame, WildMove};
+use rust_tac_toe_engine::{GameStatus, Player};
 use wasm_bindgen::prelude::*;
 
+/// The solved game, generated by build.rs
+fn tablebase() -> &'static Tablebase {
+    rust_tac_toe_engine::embedded_tablebase!()
+}
+
+/// Playouts the computer opponent searches per Connect Four move at each difficulty
+fn connect_four_playouts(difficulty: Difficulty) -> u32 {
+    match difficulty {
+        Difficulty::Random => 20,
+        Difficulty::Easy => 150,
+        Difficulty::Medium => 600,
+        Difficulty::Hard => 3000,
+        Difficulty::Perfect => 12000,
+    }
+}
+
 /// WASM-friendly wrapper for the game engine
 #[wasm_bindgen]
 pub struct WasmGameEngine {
     engine: GameEngine,
+    opponent: Option<AiPlayer>,
+    /// The Connect Four game while `new_game("connect4")` is in effect, with `engine`
+    /// sitting idle. Moves and legal moves are then board indices, top row first
+    gravity: Option<MnkEngine>,
+    /// The computer opponent in Connect Four, searching as many playouts as the
+    /// difficulty allows
+    searcher: Mcts,
+}
+
+impl Default for WasmGameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[wasm_bindgen]
@@ -20,11 +57,23 @@ impl WasmGameEngine {
     pub fn new() -> WasmGameEngine {
         WasmGameEngine {
             engine: GameEngine::new(),
+            opponent: None,
+            gravity: None,
+            searcher: Mcts::new(
+                Budget::Iterations(connect_four_playouts(Difficulty::Hard)),
+                0,
+            ),
         }
     }
 
-    /// Play a move at the given position (0-8)
+    /// Play a move at the given position (0-8). In Connect Four the position is a
+    /// board index, which must be the lowest empty cell of its column
     pub fn play_move(&mut self, position: u8) -> Result<(), JsError> {
+        if let Some(gravity) = self.gravity.as_mut() {
+            return gravity
+                .play_move(position as usize)
+                .map_err(|e| JsError::new(&format!("{:?}", e)));
+        }
         let pos = Position::new(position).ok_or(JsError::new("Invalid position (must be 0-8)"))?;
 
         self.engine
@@ -32,25 +81,332 @@ impl WasmGameEngine {
             .map_err(|e| JsError::new(&format!("{:?}", e)))
     }
 
-    /// Reset the game
+    /// Drop a piece into the given column (0-6) of a Connect Four game. Returns the
+    /// board index it landed on, counting rows from the top
+    pub fn play_column(&mut self, column: usize) -> Result<usize, JsError> {
+        let gravity = self
+            .gravity
+            .as_mut()
+            .ok_or(JsError::new("Only Connect Four moves pick a column"))?;
+        gravity
+            .play_column(column)
+            .map_err(|e| JsError::new(&format!("{:?}", e)))
+    }
+
+    /// Reset the game, keeping its rules
     pub fn reset(&mut self) {
-        self.engine.reset();
+        match self.gravity.as_mut() {
+            Some(gravity) => gravity.reset(),
+            None => self.engine.reset(),
+        }
     }
 
-    /// Get the complete game state as JSON
+    /// Start a new game under "standard" rules, "misere" rules, where completing
+    /// a line loses, or "connect4", where pieces drop down a 7×6 board and four in a
+    /// row wins
+    pub fn new_game(&mut self, rules: &str) -> Result<(), JsError> {
+        if rules == "connect4" {
+            self.gravity = Some(MnkEngine::connect_four());
+            return Ok(());
+        }
+        self.gravity = None;
+        let rules =
+            RuleSet::from_name(rules).ok_or(JsError::new(&format!("Unknown rules: {}", rules)))?;
+        self.engine = GameEngine::with_rules(rules);
+        Ok(())
+    }
+
+    /// Take back the last move. Returns false if there was nothing to undo, which is
+    /// always the case in Connect Four
+    pub fn undo(&mut self) -> bool {
+        self.gravity.is_none() && self.engine.undo().is_some()
+    }
+
+    /// Replay the last undone move. Returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.gravity.is_none() && self.engine.redo().is_some()
+    }
+
+    /// The positions played so far, in order. Not recorded for Connect Four
+    pub fn history(&self) -> Vec<u8> {
+        if self.gravity.is_some() {
+            return Vec::new();
+        }
+        self.engine
+            .history()
+            .iter()
+            .map(|pos| pos.to_index() as u8)
+            .collect()
+    }
+
+    /// Get the complete game state as JSON. In Connect Four the board has
+    /// `width * height` cells, top row first, and a winning line lists every cell in it
     pub fn get_state(&self) -> Result<JsValue, JsError> {
-        return serde_wasm_bindgen::to_value(&self.engine)
-            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)));
+        let state = match &self.gravity {
+            Some(gravity) => serde_wasm_bindgen::to_value(gravity),
+            None => serde_wasm_bindgen::to_value(&self.engine),
+        };
+        state.map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
     }
 
-    /// Check if a move is valid at the given position
+    /// Check if a move is valid at the given position, a board index in Connect Four
     pub fn is_valid_move(&self, position: u8) -> bool {
+        if let Some(gravity) = &self.gravity {
+            return gravity.validate_move(position as usize).is_ok();
+        }
         let pos = match Position::new(position) {
             Some(p) => p,
             None => return false,
         };
         self.engine.validate_move(pos).is_ok()
     }
+
+    /// The positions the current player may play, in board order. In Connect Four
+    /// these are the board indices a piece would land on, one per column that is not
+    /// full. Empty once the game is over
+    pub fn legal_moves(&self) -> Vec<u8> {
+        if let Some(gravity) = &self.gravity {
+            return gravity
+                .legal_moves()
+                .into_iter()
+                .map(|index| index as u8)
+                .collect();
+        }
+        self.engine
+            .legal_moves()
+            .map(|pos| pos.to_index() as u8)
+            .collect()
+    }
+
+    /// Notable moves for the current player as `{ wins, blocks, forks }`, each a list of
+    /// positions: moves that win at once, moves that stop the opponent winning next
+    /// turn, and moves that create two threats at once
+    pub fn get_hints(&self) -> Result<JsValue, JsError> {
+        if self.gravity.is_some() {
+            return Err(JsError::new("Hints are only available for tic-tac-toe"));
+        }
+        serde_wasm_bindgen::to_value(&self.engine.hints())
+            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+    }
+
+    /// Perfect-play verdict for the current position as `{ value, distance }`, where
+    /// value is "xWins", "oWins" or "draw" and distance is the number of moves left.
+    /// `null` if the position cannot occur in a real game or is not under standard rules
+    pub fn solve_position(&self) -> Result<JsValue, JsError> {
+        if self.gravity.is_some() {
+            return Ok(JsValue::NULL);
+        }
+        serde_wasm_bindgen::to_value(&tablebase().get(&self.engine))
+            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+    }
+
+    /// A perfect move for the current player, looked up in the tablebase, or found by
+    /// search for misère games. Returns `undefined` if the game is over or is Connect Four
+    pub fn perfect_move(&self) -> Option<u8> {
+        if self.gravity.is_some() {
+            return None;
+        }
+        tablebase()
+            .best_move(&self.engine)
+            .or_else(|| best_move(&self.engine))
+            .map(|pos| pos.to_index() as u8)
+    }
+
+    /// Choose the computer opponent ("random", "easy", "medium", "hard" or "perfect"),
+    /// or pass `undefined` for two human players. In Connect Four every difficulty
+    /// plays by tree search, with more playouts the harder it is
+    pub fn set_difficulty(&mut self, difficulty: Option<String>, seed: u32) -> Result<(), JsError> {
+        let difficulty = match difficulty {
+            Some(name) => Some(
+                Difficulty::from_name(&name)
+                    .ok_or(JsError::new(&format!("Unknown difficulty: {}", name)))?,
+            ),
+            None => None,
+        };
+        let playouts = connect_four_playouts(difficulty.unwrap_or(Difficulty::Hard));
+        self.searcher = Mcts::new(Budget::Iterations(playouts), seed as u64);
+        self.opponent = difficulty.map(|difficulty| AiPlayer::new(difficulty, seed as u64));
+        Ok(())
+    }
+
+    /// Let the computer opponent play for the current player.
+    /// Returns the position played, a board index in Connect Four, or `undefined` if
+    /// no move was made.
+    pub fn play_ai_move(&mut self) -> Result<Option<u8>, JsError> {
+        let Some(opponent) = self.opponent.as_mut() else {
+            return Err(JsError::new("No computer opponent selected"));
+        };
+        if let Some(gravity) = self.gravity.as_mut() {
+            let Some(index) = self.searcher.search(gravity) else {
+                return Ok(None);
+            };
+            gravity
+                .play_move(index)
+                .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+            return Ok(Some(index as u8));
+        }
+        let Some(pos) = opponent.choose_move(&self.engine) else {
+            return Ok(None);
+        };
+        self.engine
+            .play_move(pos)
+            .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+        Ok(Some(pos.to_index() as u8))
+    }
+}
+
+/// WASM-friendly wrapper for wild tic-tac-toe, where each move places an X or an O
+/// and whoever completes a line of either symbol wins
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct WasmWildGame {
+    game: WildGame,
+}
+
+#[wasm_bindgen]
+impl WasmWildGame {
+    /// Create a new wild game
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmWildGame {
+        WasmWildGame::default()
+    }
+
+    /// Place `symbol` ("X" or "O", any case) at the given position (0-8)
+    pub fn play_move(&mut self, position: u8, symbol: &str) -> Result<(), JsError> {
+        let symbol = match symbol.to_uppercase().as_str() {
+            "X" => Player::X,
+            "O" => Player::O,
+            _ => return Err(JsError::new(&format!("Unknown symbol: {}", symbol))),
+        };
+        let mv = WildMove::new(position, symbol)
+            .ok_or(JsError::new("Invalid position (must be 0-8)"))?;
+
+        self.game
+            .play_move(mv)
+            .map_err(|e| JsError::new(&format!("{:?}", e)))
+    }
+
+    pub fn reset(&mut self) {
+        self.game.reset();
+    }
+
+    /// Take back the last move. Returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        self.game.undo().is_some()
+    }
+
+    /// Replay the last undone move. Returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.game.redo().is_some()
+    }
+
+    /// The empty positions, in board order. Either symbol may go in any of them.
+    /// Empty once the game is over
+    pub fn legal_moves(&self) -> Vec<u8> {
+        if *self.game.status() != GameStatus::Ongoing {
+            return Vec::new();
+        }
+        (0..9)
+            .filter(|&i| self.game.board()[i as usize].is_none())
+            .collect()
+    }
+
+    /// Get the game state as JSON, shaped like `WasmGameEngine::get_state`. The board
+    /// holds symbols, while `currentPlayer` and a winner's `player` name the players
+    /// by turn order: "X" moves first and "O" second, whatever symbols they placed
+    pub fn get_state(&self) -> Result<JsValue, JsError> {
+        serde_wasm_bindgen::to_value(&self.game)
+            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+    }
+}
+
+/// WASM-friendly wrapper for Notakto: both players place X's on several boards, a
+/// board with three in a row is dead, and whoever kills the last board loses.
+/// Moves are numbered `board * 9 + cell`
+#[wasm_bindgen]
+pub struct WasmNotaktoGame {
+    game: NotaktoGame,
+}
+
+#[wasm_bindgen]
+impl WasmNotaktoGame {
+    /// Create a new game on 1 to 9 boards
+    #[wasm_bindgen(constructor)]
+    pub fn new(boards: usize) -> Result<WasmNotaktoGame, JsError> {
+        let rules = Notakto::new(boards).ok_or(JsError::new(&format!(
+            "Invalid board count (must be 1-{})",
+            notakto::MAX_BOARDS
+        )))?;
+        Ok(WasmNotaktoGame {
+            game: NotaktoGame::new(rules),
+        })
+    }
+
+    /// Place an X in the given cell (0-8) of the given board
+    pub fn play_move(&mut self, board: usize, cell: u8) -> Result<(), JsError> {
+        let mv = NotaktoMove::new(board, cell).ok_or(JsError::new("Invalid cell (must be 0-8)"))?;
+
+        self.game
+            .play_move(mv)
+            .map_err(|e| JsError::new(&format!("{:?}", e)))
+    }
+
+    pub fn reset(&mut self) {
+        self.game.reset();
+    }
+
+    /// Take back the last move. Returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        self.game.undo().is_some()
+    }
+
+    /// Replay the last undone move. Returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.game.redo().is_some()
+    }
+
+    /// The moves the current player may play, numbered `board * 9 + cell`. Empty once
+    /// the game is over
+    pub fn legal_moves(&self) -> Vec<u8> {
+        self.game.legal_moves().iter().map(move_index).collect()
+    }
+
+    /// The boards that hold three in a row and are out of play
+    pub fn dead_boards(&self) -> Vec<usize> {
+        (0..self.game.board().len())
+            .filter(|&board| notakto::is_dead(&self.game.board()[board]))
+            .collect()
+    }
+
+    /// Get the game state as JSON: `board` is a list of boards, each nine booleans for
+    /// the filled cells. Both players play X, so `currentPlayer` and a winner's
+    /// `player` name the players by turn order: "X" moves first and "O" second. A
+    /// winner's `line` is `[board, [cells]]`, the line that killed the last board
+    pub fn get_state(&self) -> Result<JsValue, JsError> {
+        serde_wasm_bindgen::to_value(&self.game)
+            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+    }
+
+    /// A perfect move for the current player, or `undefined` if the game is over
+    pub fn perfect_move(&self) -> Option<u8> {
+        notakto::best_move(&self.game).as_ref().map(move_index)
+    }
+
+    /// Play a perfect move for the current player.
+    /// Returns the move played, or `undefined` if the game is over.
+    pub fn play_ai_move(&mut self) -> Result<Option<u8>, JsError> {
+        let Some(mv) = notakto::best_move(&self.game) else {
+            return Ok(None);
+        };
+        self.game
+            .play_move(mv)
+            .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+        Ok(Some(move_index(&mv)))
+    }
+}
+
+fn move_index(mv: &NotaktoMove) -> u8 {
+    (mv.board * 9 + mv.cell.to_index()) as u8
 }
 
 #[wasm_bindgen(start)]
@@ -1,4 +1,13 @@
 let game;
+// Notakto is a separate game; `notaktoMode` says which one is on the page
+let notakto;
+let notaktoMode = false;
+// Connect Four is played by `game` under the "connect4" rules
+let connectFourMode = false;
+
+function activeGame() {
+  return notaktoMode ? notakto : game;
+}
 
 async function initializeGame() {
   try {
@@ -10,10 +19,11 @@ async function initializeGame() {
         "WASM bindings not found. Ensure Trunk is set up correctly."
       );
     }
-    const { WasmGameEngine } = bindings;
+    const { WasmGameEngine, WasmNotaktoGame } = bindings;
 
     // Create a new game instance
     game = new WasmGameEngine();
+    notakto = new WasmNotaktoGame(3);
 
     // Hide loading and show content
     document.getElementById("loading").style.display = "none";
@@ -33,6 +43,14 @@ async function initializeGame() {
 let cellElements = [];
 
 function renderBoard() {
+  if (notaktoMode) {
+    renderNotaktoBoards();
+    return;
+  }
+  if (connectFourMode) {
+    renderConnectFour();
+    return;
+  }
   const boardDiv = document.getElementById("board");
   const state = game.get_state();
   const board = state.board;
@@ -50,8 +68,8 @@ function renderBoard() {
   }
 
   // Otherwise update existing
-  const isGameOver = gameStatus !== "Ongoing";
   const winningLine = gameStatus === "Win" ? state.status.value.line : [];
+  const legalMoves = game.legal_moves();
   cellElements.forEach((cell, i) => {
     const val = board[i];
     cell.textContent = val;
@@ -59,9 +77,7 @@ function renderBoard() {
     if (val === "X") cell.classList.add("x");
     if (val === "O") cell.classList.add("o");
 
-    const isGameOver = gameStatus !== "Ongoing";
-    const isOccupied = val !== undefined;
-    cell.disabled = isGameOver || isOccupied;
+    cell.disabled = !legalMoves.includes(i);
 
     if (winningLine.includes(i)) {
       cell.classList.add("winning-cell");
@@ -69,21 +85,84 @@ function renderBoard() {
   });
 }
 
-function updateStatus() {
+// Draw every Notakto board from scratch, greying out the dead ones
+function renderNotaktoBoards() {
+  const container = document.getElementById("notaktoBoards");
+  const state = notakto.get_state();
+  const legalMoves = notakto.legal_moves();
+  const deadBoards = notakto.dead_boards();
+  const [lastBoard, lastLine] =
+    state.status.type === "Win" ? state.status.value.line : [-1, []];
+
+  container.replaceChildren();
+  state.board.forEach((cells, b) => {
+    const boardDiv = document.createElement("div");
+    boardDiv.className = "board mini";
+    if (deadBoards.includes(b)) boardDiv.classList.add("dead");
+    cells.forEach((filled, i) => {
+      const cell = document.createElement("button");
+      cell.className = "cell x";
+      cell.textContent = filled ? "X" : "";
+      cell.disabled = !legalMoves.includes(b * 9 + i);
+      cell.onclick = () => makeNotaktoMove(b, i);
+      if (b === lastBoard && lastLine.includes(i)) {
+        cell.classList.add("winning-cell");
+      }
+      boardDiv.appendChild(cell);
+    });
+    container.appendChild(boardDiv);
+  });
+}
+
+// Draw the Connect Four grid from scratch. Clicking any cell drops a piece down its
+// column, and the piece in `droppedCell` is animated falling from the top row
+function renderConnectFour(droppedCell = -1) {
+  const container = document.getElementById("connectFour");
   const state = game.get_state();
+  const legalCells = game.legal_moves();
+  const winningLine = state.status.type === "Win" ? state.status.value.line : [];
+
+  container.style.gridTemplateColumns = `repeat(${state.width}, 1fr)`;
+  container.replaceChildren();
+  state.board.forEach((val, i) => {
+    const column = i % state.width;
+    const cell = document.createElement("button");
+    cell.className = "cell";
+    cell.textContent = val;
+    if (val === "X") cell.classList.add("x");
+    if (val === "O") cell.classList.add("o");
+    if (winningLine.includes(i)) cell.classList.add("winning-cell");
+    if (i === droppedCell) {
+      cell.classList.add("dropping");
+      cell.style.setProperty("--drop-rows", Math.floor(i / state.width));
+    }
+    cell.disabled = !legalCells.some((index) => index % state.width === column);
+    cell.onclick = () => makeColumnMove(column);
+    container.appendChild(cell);
+  });
+}
 
-  document.getElementById("currentPlayer").textContent = state.currentPlayer;
+// Both Notakto players place X's, so they go by turn order instead
+function playerName(player) {
+  if (!notaktoMode) return player;
+  return player === "X" ? "Player 1" : "Player 2";
+}
+
+function updateStatus() {
+  const state = activeGame().get_state();
+
+  document.getElementById("currentPlayer").textContent = playerName(state.currentPlayer);
   document.getElementById("status").textContent = state.status.type;
 
   const statusMessage = document.getElementById("statusMessage");
   if (state.status.type === "Ongoing") {
-    statusMessage.textContent = `${state.currentPlayer}'s turn`;
+    statusMessage.textContent = `${playerName(state.currentPlayer)}'s turn`;
     statusMessage.className = "status-message ongoing";
   } else if (state.status.type === "Draw") {
     statusMessage.textContent = "🤝 It's a Draw!";
     statusMessage.className = "status-message draw";
   } else if (state.status.type === "Win") {
-    const winner = state.status.value.player;
+    const winner = playerName(state.status.value.player);
     statusMessage.textContent = `🎉 ${winner} Wins!`;
     statusMessage.className = "status-message win";
   }
@@ -92,6 +171,35 @@ function updateStatus() {
 function makeMove(position) {
   try {
     game.play_move(position);
+    playOpponentMove();
+    renderBoard();
+    updateStatus();
+  } catch (err) {
+    console.error("Move failed:", err);
+  }
+}
+
+function makeColumnMove(column) {
+  try {
+    renderConnectFour(game.play_column(column));
+    updateStatus();
+    // Let the piece land before the computer replies
+    if (isOpponentTurn()) {
+      setTimeout(() => {
+        const reply = game.play_ai_move();
+        renderConnectFour(reply ?? -1);
+        updateStatus();
+      }, 400);
+    }
+  } catch (err) {
+    console.error("Move failed:", err);
+  }
+}
+
+function makeNotaktoMove(board, cell) {
+  try {
+    notakto.play_move(board, cell);
+    playOpponentMove();
     renderBoard();
     updateStatus();
   } catch (err) {
@@ -99,9 +207,82 @@ function makeMove(position) {
   }
 }
 
+// The computer always plays O, or second in Notakto, where it always plays perfectly
+let hasOpponent = false;
+
+function playOpponentMove() {
+  if (isOpponentTurn()) {
+    activeGame().play_ai_move();
+  }
+}
+
+function isOpponentTurn() {
+  const state = activeGame().get_state();
+  return hasOpponent && state.status.type === "Ongoing" && state.currentPlayer === "O";
+}
+
+// Against the computer, take back (or replay) its reply along with your move
+window.undoMove = function () {
+  const game = activeGame();
+  if (game.undo() && isOpponentTurn()) game.undo();
+  renderBoard();
+  updateStatus();
+};
+
+window.redoMove = function () {
+  const game = activeGame();
+  if (game.redo() && isOpponentTurn() && !game.redo()) playOpponentMove();
+  renderBoard();
+  updateStatus();
+};
+
+window.setOpponent = function (difficulty) {
+  try {
+    hasOpponent = difficulty !== "";
+    game.set_difficulty(hasOpponent ? difficulty : undefined, Date.now() >>> 0);
+    playOpponentMove();
+    renderBoard();
+    updateStatus();
+  } catch (err) {
+    console.error("Changing opponent failed:", err);
+  }
+};
+
+// Changing the rules starts a new game
+window.setRules = function (rules) {
+  try {
+    notaktoMode = rules === "notakto";
+    connectFourMode = rules === "connect4";
+    document.getElementById("board").style.display =
+      notaktoMode || connectFourMode ? "none" : "";
+    document.getElementById("connectFour").style.display = connectFourMode ? "" : "none";
+    document.getElementById("notaktoBoards").style.display = notaktoMode ? "" : "none";
+    document.getElementById("boardCount").style.display = notaktoMode ? "" : "none";
+    if (notaktoMode) {
+      notakto.reset();
+    } else {
+      game.new_game(rules);
+    }
+    renderBoard();
+    updateStatus();
+  } catch (err) {
+    console.error("Changing rules failed:", err);
+  }
+};
+
+window.setBoardCount = function (boards) {
+  try {
+    notakto = new window.wasmBindings.WasmNotaktoGame(Number(boards));
+    renderBoard();
+    updateStatus();
+  } catch (err) {
+    console.error("Changing the board count failed:", err);
+  }
+};
+
 window.resetGame = function () {
   try {
-    game.reset();
+    activeGame().reset();
     renderBoard();
     updateStatus();
   } catch (err) {